@@ -0,0 +1,241 @@
+//! Recorded answers, to check a run against.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Name of the answers file, looked up next to the `dayN` input files.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Expected results per day and part, read from a small subset of TOML:
+///
+/// ```toml
+/// [day1]
+/// part1 = 1692
+/// part2 = "1724"
+/// ```
+///
+/// Values are integers, basic strings or `'''` multi-line literal strings.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers(HashMap<(u32, u8), String>);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnswersError {
+    InvalidSection(usize),
+    InvalidKey(usize),
+    InvalidValue(usize),
+    KeyOutsideSection(usize),
+    Unterminated(usize),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswersError::InvalidSection(line) => write!(f, "line {}: expected [dayN]", line),
+            AnswersError::InvalidKey(line) => write!(f, "line {}: expected part1 or part2", line),
+            AnswersError::InvalidValue(line) => write!(f, "line {}: invalid value", line),
+            AnswersError::KeyOutsideSection(line) => {
+                write!(f, "line {}: answer before any [dayN] section", line)
+            }
+            AnswersError::Unterminated(line) => write!(f, "line {}: unterminated string", line),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    Missing,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail(expected) if expected.contains('\n') => {
+                write!(f, "FAIL, expected\n{}", expected)
+            }
+            Verdict::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Verdict::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+/// Running count of verdicts, for the summary after a `--check` run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl Tally {
+    pub fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail(_) => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+        }
+    }
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers, AnswersError> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+        let mut lines = input.lines().enumerate().map(|(i, l)| (i + 1, l));
+        while let Some((number, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[') {
+                let parsed = section
+                    .strip_suffix(']')
+                    .and_then(|s| s.trim().strip_prefix("day"))
+                    .and_then(|s| s.parse().ok());
+                day = Some(parsed.ok_or(AnswersError::InvalidSection(number))?);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(AnswersError::InvalidKey(number))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(AnswersError::InvalidKey(number)),
+            };
+            let day = day.ok_or(AnswersError::KeyOutsideSection(number))?;
+            let value = value.trim();
+            let value = if let Some(first) = value.strip_prefix("'''") {
+                let mut text = first.to_string();
+                while !text.contains("'''") {
+                    let (_, next) = lines.next().ok_or(AnswersError::Unterminated(number))?;
+                    text.push('\n');
+                    text.push_str(next);
+                }
+                let text = &text[..text.find("'''").unwrap()];
+                //Like TOML, a newline straight after the opening quotes is not part of the value
+                text.strip_prefix('\n').unwrap_or(text).to_string()
+            } else if let Some(quoted) = value.strip_prefix('"') {
+                quoted
+                    .strip_suffix('"')
+                    .ok_or(AnswersError::Unterminated(number))?
+                    .to_string()
+            } else if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit() || c == '-') {
+                value.to_string()
+            } else {
+                return Err(AnswersError::InvalidValue(number));
+            };
+            answers.insert((day, part), value);
+        }
+        Ok(Answers(answers))
+    }
+
+    /// The days with at least one answer, in no particular order and once per answer.
+    pub fn days(&self) -> impl Iterator<Item = u32> + '_ {
+        self.0.keys().map(|(day, _)| *day)
+    }
+
+    pub fn get(&self, day: u32, part: u8) -> Option<&str> {
+        self.0.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn verify(&self, day: u32, part: u8, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            None => Verdict::Missing,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.to_string()),
+        }
+    }
+}
+
+/// The recorded answer for the checked in input of `day`.
+#[cfg(test)]
+pub fn recorded(day: u32, part: u8) -> String {
+    let input = std::fs::read_to_string(format!("input/{}", ANSWERS_FILE)).unwrap();
+    Answers::parse(&input)
+        .unwrap()
+        .get(day, part)
+        .unwrap()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let input = "\
+# comment
+[day1]
+part1 = 1692
+part2 = \"1724\"
+
+[day13]
+part2 = '''
+#..
+.#.
+'''
+";
+        let answers = Answers::parse(input).unwrap();
+        assert_eq!(Some("1692"), answers.get(1, 1));
+        assert_eq!(Some("1724"), answers.get(1, 2));
+        assert_eq!(None, answers.get(13, 1));
+        assert_eq!(Some("#..\n.#.\n"), answers.get(13, 2));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(AnswersError::KeyOutsideSection(1)),
+            Answers::parse("part1 = 3")
+        );
+        assert_eq!(
+            Err(AnswersError::InvalidSection(1)),
+            Answers::parse("[part1]")
+        );
+        assert_eq!(
+            Err(AnswersError::InvalidKey(2)),
+            Answers::parse("[day1]\npart3 = 3")
+        );
+        assert_eq!(
+            Err(AnswersError::InvalidValue(2)),
+            Answers::parse("[day1]\npart1 = three")
+        );
+        assert_eq!(
+            Err(AnswersError::Unterminated(2)),
+            Answers::parse("[day1]\npart1 = '''\n#.#")
+        );
+    }
+
+    #[test]
+    fn verify() {
+        let answers = Answers::parse("[day2]\npart1 = 5").unwrap();
+        assert_eq!(Verdict::Pass, answers.verify(2, 1, "5"));
+        assert_eq!(Verdict::Fail("5".to_string()), answers.verify(2, 1, "6"));
+        assert_eq!(Verdict::Missing, answers.verify(2, 2, "5"));
+
+        let mut tally = Tally::default();
+        tally.add(&answers.verify(2, 1, "5"));
+        tally.add(&answers.verify(2, 1, "6"));
+        assert_eq!("1 passed, 1 failed, 0 missing", tally.to_string());
+    }
+
+    #[test]
+    fn recorded_answers_parse() {
+        assert_eq!("1692", recorded(1, 1));
+    }
+}
@@ -0,0 +1,333 @@
+//! Repeated timing of a single day, and baselines to compare those timings against.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::runner::{self, millis};
+use crate::solution::Day;
+
+/// Summary of the timings of one phase over all runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarizes `samples`, which must not be empty. The 95th percentile uses the nearest rank.
+    pub fn new(samples: &[Duration]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2
+        } else {
+            sorted[n / 2]
+        };
+        let p95 = sorted[(n * 95).div_ceil(100) - 1];
+        Stats {
+            min: sorted[0],
+            median,
+            p95,
+        }
+    }
+}
+
+/// The phases every benchmark reports on, as named in tables and baseline files.
+pub const PHASES: [&str; 4] = ["parse", "part1", "part2", "total"];
+
+pub struct BenchReport {
+    pub day: u32,
+    pub runs: usize,
+    pub warmup: usize,
+    /// One entry per phase in [`PHASES`].
+    pub phases: [Stats; 4],
+}
+
+/// Runs `day` `warmup` times untimed, then `runs` times to collect timings.
+pub fn bench(day: &Day, input: &str, warmup: usize, runs: usize) -> Result<BenchReport> {
+    if runs == 0 {
+        return Err(Error::invalid("a benchmark needs at least one run"));
+    }
+    for _ in 0..warmup {
+        runner::run(day, input)?;
+    }
+    let mut samples: [Vec<Duration>; 4] = Default::default();
+    for _ in 0..runs {
+        let report = runner::run(day, input)?;
+        let timings = [
+            report.parse,
+            report.part_one.duration,
+            report.part_two.duration,
+            report.total(),
+        ];
+        for (phase, timing) in samples.iter_mut().zip(timings) {
+            phase.push(timing);
+        }
+    }
+    Ok(BenchReport {
+        day: day.number,
+        runs,
+        warmup,
+        phases: samples.map(|s| Stats::new(&s)),
+    })
+}
+
+impl BenchReport {
+    pub fn table(&self) -> String {
+        let header = ["Phase", "Min", "Median", "p95"].map(String::from);
+        let rows: Vec<_> = PHASES
+            .iter()
+            .zip(self.phases.iter())
+            .map(|(phase, stats)| {
+                [
+                    phase.to_string(),
+                    runner::format_duration(stats.min),
+                    runner::format_duration(stats.median),
+                    runner::format_duration(stats.p95),
+                ]
+            })
+            .collect();
+        runner::format_table(&header, &rows, None)
+    }
+
+    fn medians(&self) -> [f64; 4] {
+        self.phases.map(|s| millis(s.median))
+    }
+}
+
+/// Median milliseconds per phase and day, read from and written as a small subset of TOML:
+///
+/// ```toml
+/// [day15]
+/// parse = 0.412
+/// part1 = 9.871
+/// part2 = 251.300
+/// total = 261.583
+/// ```
+///
+/// A phase missing from a day's section has no baseline, and is not compared.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline(BTreeMap<u32, [Option<f64>; 4]>);
+
+impl Baseline {
+    pub fn parse(input: &str) -> Result<Baseline> {
+        let mut days = BTreeMap::new();
+        let mut day = None;
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| Error::parse(message).on_line(i + 1);
+            if let Some(section) = line.strip_prefix('[') {
+                let parsed = section
+                    .strip_suffix(']')
+                    .and_then(|s| s.trim().strip_prefix("day"))
+                    .and_then(|s| s.parse().ok());
+                day = Some(parsed.ok_or_else(|| error("expected [dayN]"))?);
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected phase = milliseconds"))?;
+            let phase = PHASES
+                .iter()
+                .position(|p| *p == key.trim())
+                .ok_or_else(|| error("expected parse, part1, part2 or total"))?;
+            let value: f64 = value
+                .trim()
+                .parse()
+                .map_err(|_| error("expected milliseconds"))?;
+            let day = day.ok_or_else(|| error("timing before any [dayN] section"))?;
+            let medians: &mut [Option<f64>; 4] = days.entry(day).or_default();
+            medians[phase] = Some(value);
+        }
+        Ok(Baseline(days))
+    }
+
+    /// Records the medians of `report`, replacing what was saved for its day.
+    pub fn record(&mut self, report: &BenchReport) {
+        self.0.insert(report.day, report.medians().map(Some));
+    }
+
+    /// Compares `report` with the saved medians of its day, flagging phases that got
+    /// slower by more than `threshold` percent. `None` when the day has no baseline.
+    pub fn compare(&self, report: &BenchReport, threshold: f64) -> Option<Comparison> {
+        let baseline = self.0.get(&report.day)?;
+        let current = report.medians();
+        let phases = PHASES
+            .iter()
+            .enumerate()
+            .filter_map(|(i, phase)| {
+                let baseline = baseline[i]?;
+                Some(PhaseChange {
+                    phase,
+                    baseline,
+                    current: current[i],
+                    regressed: current[i] > baseline * (1.0 + threshold / 100.0),
+                })
+            })
+            .collect();
+        Some(Comparison { phases })
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (day, medians)) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[day{}]", day)?;
+            for (phase, median) in PHASES.iter().zip(medians) {
+                if let Some(median) = median {
+                    writeln!(f, "{} = {:.3}", phase, median)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PhaseChange {
+    pub phase: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    pub regressed: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Comparison {
+    pub phases: Vec<PhaseChange>,
+}
+
+impl Comparison {
+    pub fn regressed(&self) -> bool {
+        self.phases.iter().any(|p| p.regressed)
+    }
+
+    pub fn table(&self) -> String {
+        let header = ["Phase", "Baseline", "Median", "Change"].map(String::from);
+        let rows: Vec<_> = self
+            .phases
+            .iter()
+            .map(|p| {
+                let mut change = if p.baseline > 0.0 {
+                    format!("{:+.1}%", (p.current / p.baseline - 1.0) * 100.0)
+                } else {
+                    "-".to_string()
+                };
+                if p.regressed {
+                    change.insert_str(0, "REGRESSION ");
+                }
+                [
+                    p.phase.to_string(),
+                    format!("{:.3} ms", p.baseline),
+                    format!("{:.3} ms", p.current),
+                    change,
+                ]
+            })
+            .collect();
+        runner::format_table(&header, &rows, None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn report(day: u32, medians: [u64; 4]) -> BenchReport {
+        BenchReport {
+            day,
+            runs: 1,
+            warmup: 0,
+            phases: medians.map(|m| Stats::new(&[ms(m)])),
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let samples: Vec<_> = (1..=20).rev().map(ms).collect();
+        assert_eq!(
+            Stats {
+                min: ms(1),
+                median: Duration::from_micros(10_500),
+                p95: ms(19),
+            },
+            Stats::new(&samples)
+        );
+        assert_eq!(
+            Stats {
+                min: ms(3),
+                median: ms(3),
+                p95: ms(3),
+            },
+            Stats::new(&[ms(3)])
+        );
+    }
+
+    #[test]
+    fn baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.record(&report(15, [1, 10, 250, 261]));
+        baseline.record(&report(3, [0, 1, 2, 3]));
+        let saved = baseline.to_string();
+        assert!(saved.starts_with("[day3]\nparse = 0.000\n"));
+        assert_eq!(Ok(baseline), Baseline::parse(&saved));
+        assert_eq!(
+            "parse error at line 2: expected parse, part1, part2 or total",
+            Baseline::parse("[day1]\npart3 = 1.0")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn compare() {
+        let mut baseline = Baseline::default();
+        baseline.record(&report(15, [1, 10, 250, 261]));
+        assert_eq!(None, baseline.compare(&report(16, [1, 1, 1, 3]), 10.0));
+
+        let comparison = baseline
+            .compare(&report(15, [1, 10, 300, 311]), 10.0)
+            .unwrap();
+        assert!(comparison.regressed());
+        assert_eq!(
+            vec![false, false, true, true],
+            comparison
+                .phases
+                .iter()
+                .map(|p| p.regressed)
+                .collect::<Vec<_>>()
+        );
+        let comparison = baseline
+            .compare(&report(15, [1, 10, 270, 281]), 10.0)
+            .unwrap();
+        assert!(!comparison.regressed());
+
+        //Phases the baseline doesn't have are left out, not counted as regressions
+        let baseline = Baseline::parse("[day15]\npart2 = 250.0").unwrap();
+        let comparison = baseline
+            .compare(&report(15, [1, 10, 260, 271]), 10.0)
+            .unwrap();
+        assert!(!comparison.regressed());
+        assert_eq!(
+            vec!["part2"],
+            comparison
+                .phases
+                .iter()
+                .map(|p| p.phase)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("[day15]\npart2 = 250.000\n", baseline.to_string());
+    }
+}
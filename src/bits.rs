@@ -0,0 +1,227 @@
+//! Packed bit streams, most significant bit first, like the BITS transmissions of day 16.
+
+use crate::error::{Error, Location, Result};
+
+/// Packs hexadecimal digits two to a byte. An odd last digit fills the high half of a byte.
+pub fn hex_to_bytes(input: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(input.len().div_ceil(2));
+    for (i, c) in input.chars().enumerate() {
+        let digit = c.to_digit(16).ok_or_else(|| {
+            Error::parse(format!("expected a hexadecimal digit, found {:?}", c))
+                .at(Location::new(1, i + 1))
+        })? as u8;
+        if i % 2 == 0 {
+            bytes.push(digit << 4);
+        } else if let Some(last) = bytes.last_mut() {
+            *last |= digit;
+        }
+    }
+    Ok(bytes)
+}
+
+/// A cursor over the bits of a byte slice.
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader {
+            bytes,
+            pos: 0,
+            end: bytes.len() * 8,
+        }
+    }
+
+    /// Bits read so far, counted from the start of the underlying bytes.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    /// Reads the next `bits` bits as a number, or nothing if fewer are left.
+    pub fn read(&mut self, bits: u32) -> Option<u64> {
+        assert!(bits <= 64, "can't read {} bits into a u64", bits);
+        if self.remaining() < bits as usize {
+            return None;
+        }
+        let mut value = 0u64;
+        let mut left = bits;
+        while left > 0 {
+            let offset = (self.pos % 8) as u32;
+            let take = (8 - offset).min(left);
+            let byte = u64::from(self.bytes[self.pos / 8]);
+            value = value << take | (byte >> (8 - offset - take)) & ((1 << take) - 1);
+            self.pos += take as usize;
+            left -= take;
+        }
+        Some(value)
+    }
+
+    /// Splits off the next `bits` bits as a reader of their own, and skips them here.
+    pub fn split(&mut self, bits: usize) -> Option<BitReader<'a>> {
+        if self.remaining() < bits {
+            return None;
+        }
+        let split = BitReader {
+            bytes: self.bytes,
+            pos: self.pos,
+            end: self.pos + bits,
+        };
+        self.pos += bits;
+        Some(split)
+    }
+}
+
+/// Collects bits into bytes, the counterpart of [`BitReader`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bits written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes the lowest `bits` bits of `value`, which must fit in them.
+    pub fn write(&mut self, value: u64, bits: u32) {
+        assert!(
+            bits == 64 || value >> bits == 0,
+            "{} doesn't fit in {} bits",
+            value,
+            bits
+        );
+        let mut left = bits;
+        while left > 0 {
+            let offset = (self.len % 8) as u32;
+            if offset == 0 {
+                self.bytes.push(0);
+            }
+            let take = (8 - offset).min(left);
+            let chunk = (value >> (left - take)) & ((1 << take) - 1);
+            if let Some(last) = self.bytes.last_mut() {
+                *last |= (chunk << (8 - offset - take)) as u8;
+            }
+            self.len += take as usize;
+            left -= take;
+        }
+    }
+
+    /// Writes all bits of `other`.
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut bits = BitReader::new(&other.bytes);
+        let mut left = other.len;
+        while left > 0 {
+            let take = left.min(64) as u32;
+            if let Some(value) = bits.read(take) {
+                self.write(value, take);
+            }
+            left -= take as usize;
+        }
+    }
+
+    /// The bits written, with the last byte padded with zeroes.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Two hexadecimal digits per byte of [`bytes`](Self::bytes).
+    pub fn to_hex(&self) -> String {
+        self.bytes
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!(Ok(vec![0xD2, 0xFE, 0x28]), hex_to_bytes("D2FE28"));
+        assert_eq!(Ok(vec![0xab, 0xc0]), hex_to_bytes("abc"));
+        assert_eq!(Ok(vec![]), hex_to_bytes(""));
+        assert_eq!(
+            "parse error at line 1, column 3: expected a hexadecimal digit, found 'x'",
+            hex_to_bytes("D2xE28").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn read() {
+        let bytes = [0b1101_0010, 0b1111_1110, 0b0010_1000];
+        let mut bits = BitReader::new(&bytes);
+        assert_eq!(Some(0b110), bits.read(3));
+        assert_eq!(Some(0b100), bits.read(3));
+        //Across a byte boundary
+        assert_eq!(Some(0b10111), bits.read(5));
+        assert_eq!(Some(0), bits.read(0));
+        assert_eq!((11, 13), (bits.position(), bits.remaining()));
+        assert_eq!(None, bits.read(14));
+        assert_eq!(Some(0b1_1110_0010_1000), bits.read(13));
+        assert_eq!(None, bits.read(1));
+
+        let bytes = [0xff; 9];
+        let mut bits = BitReader::new(&bytes);
+        assert_eq!(Some(1), bits.read(1));
+        assert_eq!(Some(u64::MAX), bits.read(64));
+    }
+
+    #[test]
+    fn split() {
+        let bytes = [0b1010_1100, 0b1000_0000];
+        let mut bits = BitReader::new(&bytes);
+        bits.read(2);
+        let mut inner = bits.split(5).unwrap();
+        assert_eq!((7, 9), (bits.position(), bits.remaining()));
+        assert_eq!((2, 5), (inner.position(), inner.remaining()));
+        assert_eq!(Some(0b10), inner.read(2));
+        assert_eq!(None, inner.read(4));
+        assert_eq!(Some(0b110), inner.read(3));
+        assert_eq!(Some(0b01), bits.read(2));
+        assert!(bits.split(8).is_none());
+    }
+
+    #[test]
+    fn write() {
+        let mut bits = BitWriter::new();
+        bits.write(0b110, 3);
+        bits.write(0b100, 3);
+        bits.write(0b10111, 5);
+        bits.write(0, 0);
+        assert_eq!(11, bits.len());
+        bits.write(0b1_1110_0010_1000, 13);
+        assert_eq!("D2FE28", bits.to_hex());
+
+        let mut padded = BitWriter::new();
+        padded.write(1, 1);
+        assert_eq!((1, "80".to_string()), (padded.len(), padded.to_hex()));
+        padded.write(u64::MAX, 64);
+        padded.append(&bits);
+        assert_eq!(89, padded.len());
+        assert_eq!("FFFFFFFFFFFFFFFFE97F1400", padded.to_hex());
+        let mut read = BitReader::new(padded.bytes());
+        assert_eq!(Some(1), read.read(1));
+        assert_eq!(Some(u64::MAX), read.read(64));
+        assert_eq!(Some(0xD2FE28), read.read(24));
+    }
+}
@@ -0,0 +1,404 @@
+use std::fmt;
+use std::num::ParseIntError;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use aoc_2021::log::Level;
+use aoc_2021::runner::parse_selection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    pub selection: RangeInclusive<u32>,
+    pub input: Option<String>,
+    pub check: bool,
+    pub answers: Option<String>,
+    pub format: Format,
+    /// Print the decoded packets of a day 16 transmission instead of the answers.
+    pub dump: bool,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            selection: 1..=1,
+            input: None,
+            check: false,
+            answers: None,
+            format: Format::Text,
+            dump: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchArgs {
+    pub day: u32,
+    pub input: Option<String>,
+    pub runs: usize,
+    pub warmup: usize,
+    pub save: Option<String>,
+    pub baseline: Option<String>,
+    /// Percentage a median may grow over the baseline before it counts as a regression.
+    pub threshold: u32,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        BenchArgs {
+            day: 1,
+            input: None,
+            runs: 10,
+            warmup: 1,
+            save: None,
+            baseline: None,
+            threshold: 10,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FetchArgs {
+    pub day: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(Args),
+    Bench(BenchArgs),
+    Fetch(FetchArgs),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    Selection(ParseIntError),
+    MissingValue(String),
+    InvalidValue(String, String),
+    UnknownFlag(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::Selection(e) => write!(f, "Could not parse day selection: {}", e),
+            ArgsError::MissingValue(flag) => write!(f, "Missing value for {}", flag),
+            ArgsError::InvalidValue(flag, value) => {
+                write!(f, "Invalid value {} for {}", value, flag)
+            }
+            ArgsError::UnknownFlag(flag) => write!(f, "Unknown flag {}", flag),
+        }
+    }
+}
+
+/// Parses the arguments after the program name:
+/// `[days] [--input <file|->] [--check [--answers <file>]] [--format <text|json>] [--dump]`.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Args, ArgsError> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => parsed.input = Some(args.next().ok_or(ArgsError::MissingValue(arg))?),
+            "--check" => parsed.check = true,
+            "--dump" => parsed.dump = true,
+            "--answers" => parsed.answers = Some(args.next().ok_or(ArgsError::MissingValue(arg))?),
+            "--format" => {
+                parsed.format = match args.next().as_deref() {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some(value) => return Err(ArgsError::InvalidValue(arg, value.to_string())),
+                    None => return Err(ArgsError::MissingValue(arg)),
+                }
+            }
+            flag if flag.starts_with("--") => return Err(ArgsError::UnknownFlag(arg)),
+            _ => parsed.selection = parse_selection(&arg).map_err(ArgsError::Selection)?,
+        }
+    }
+    Ok(parsed)
+}
+
+fn value<T: FromStr>(
+    flag: String,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<T, ArgsError> {
+    let value = args
+        .next()
+        .ok_or_else(|| ArgsError::MissingValue(flag.clone()))?;
+    value
+        .parse()
+        .map_err(|_| ArgsError::InvalidValue(flag, value))
+}
+
+/// Parses the arguments after `bench`:
+/// `<day> [--input <file|->] [--runs <n>] [--warmup <n>] [--save <file>]
+/// [--baseline <file>] [--threshold <percent>]`.
+pub fn parse_bench_args<I: IntoIterator<Item = String>>(args: I) -> Result<BenchArgs, ArgsError> {
+    let mut parsed = BenchArgs::default();
+    let mut day = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => parsed.input = Some(value(arg, &mut args)?),
+            "--runs" => parsed.runs = value(arg, &mut args)?,
+            "--warmup" => parsed.warmup = value(arg, &mut args)?,
+            "--save" => parsed.save = Some(value(arg, &mut args)?),
+            "--baseline" => parsed.baseline = Some(value(arg, &mut args)?),
+            "--threshold" => parsed.threshold = value(arg, &mut args)?,
+            flag if flag.starts_with("--") => return Err(ArgsError::UnknownFlag(arg)),
+            _ => day = Some(arg.parse().map_err(ArgsError::Selection)?),
+        }
+    }
+    if parsed.runs == 0 {
+        return Err(ArgsError::InvalidValue(
+            "--runs".to_string(),
+            "0".to_string(),
+        ));
+    }
+    parsed.day = day.ok_or_else(|| ArgsError::MissingValue("bench".to_string()))?;
+    Ok(parsed)
+}
+
+/// Parses the arguments after `fetch`: `<day>`.
+pub fn parse_fetch_args<I: IntoIterator<Item = String>>(args: I) -> Result<FetchArgs, ArgsError> {
+    let mut day = None;
+    for arg in args {
+        match arg.as_str() {
+            flag if flag.starts_with("--") => return Err(ArgsError::UnknownFlag(arg)),
+            _ => day = Some(arg.parse().map_err(ArgsError::Selection)?),
+        }
+    }
+    let day = day.ok_or_else(|| ArgsError::MissingValue("fetch".to_string()))?;
+    Ok(FetchArgs { day })
+}
+
+/// Flags followed by a value, which is taken as is even when it looks like a log flag.
+const VALUE_FLAGS: [&str; 8] = [
+    "--input",
+    "--answers",
+    "--format",
+    "--runs",
+    "--warmup",
+    "--save",
+    "--baseline",
+    "--threshold",
+];
+
+fn is_log_flag(arg: &str) -> bool {
+    match arg {
+        "-q" | "--quiet" | "--verbose" => true,
+        _ => arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v'),
+    }
+}
+
+/// Parses the arguments after the program name, either a `bench` or `fetch` run or a regular one,
+/// along with the log level set by `-q`, `-v`, `-vv` or `-vvv` anywhere a flag can go.
+pub fn parse_command<I: IntoIterator<Item = String>>(
+    args: I,
+) -> Result<(Command, Level), ArgsError> {
+    let mut log_flags = Vec::new();
+    let mut rest = Vec::new();
+    let mut is_value = false;
+    for arg in args {
+        if !is_value && is_log_flag(&arg) {
+            log_flags.push(arg);
+            continue;
+        }
+        is_value = !is_value && VALUE_FLAGS.contains(&arg.as_str());
+        rest.push(arg);
+    }
+    let quiet = log_flags.iter().any(|f| f == "-q" || f == "--quiet");
+    let verbose = log_flags
+        .iter()
+        .map(|f| match f.as_str() {
+            "--verbose" => 1,
+            "-q" | "--quiet" => 0,
+            v => v.len() - 1,
+        })
+        .sum();
+    let level = Level::from_flags(quiet, verbose);
+
+    let mut args = rest.into_iter().peekable();
+    match args.peek().map(String::as_str) {
+        Some("bench") => {
+            args.next();
+            Ok((Command::Bench(parse_bench_args(args)?), level))
+        }
+        Some("fetch") => {
+            args.next();
+            Ok((Command::Fetch(parse_fetch_args(args)?), level))
+        }
+        _ => Ok((Command::Run(parse_args(args)?), level)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(line: &str) -> Result<Args, ArgsError> {
+        parse_args(line.split_whitespace().map(String::from))
+    }
+
+    fn command(line: &str) -> Result<Command, ArgsError> {
+        parse_command(line.split_whitespace().map(String::from)).map(|(command, _)| command)
+    }
+
+    fn level(line: &str) -> Level {
+        parse_command(line.split_whitespace().map(String::from))
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Args::default()), args(""));
+        assert_eq!(
+            Ok(Args {
+                selection: 3..=3,
+                input: Some("-".to_string()),
+                ..Args::default()
+            }),
+            args("--input - 3")
+        );
+        assert_eq!(
+            Ok(Args {
+                selection: 1..=25,
+                input: Some("mine/day3".to_string()),
+                ..Args::default()
+            }),
+            args("all --input mine/day3")
+        );
+        assert_eq!(
+            Ok(Args {
+                selection: 1..=25,
+                check: true,
+                answers: Some("mine/answers.toml".to_string()),
+                ..Args::default()
+            }),
+            args("all --check --answers mine/answers.toml")
+        );
+        assert_eq!(
+            Ok(Args {
+                format: Format::Json,
+                ..Args::default()
+            }),
+            args("--format json")
+        );
+        assert_eq!(
+            Ok(Args {
+                selection: 16..=16,
+                input: Some("-".to_string()),
+                dump: true,
+                ..Args::default()
+            }),
+            args("16 --dump --input -")
+        );
+        assert_eq!(
+            Err(ArgsError::InvalidValue(
+                "--format".to_string(),
+                "xml".to_string()
+            )),
+            args("--format xml")
+        );
+        assert_eq!(
+            Err(ArgsError::MissingValue("--input".to_string())),
+            args("3 --input")
+        );
+        assert_eq!(
+            Err(ArgsError::UnknownFlag("--inptu".to_string())),
+            args("3 --inptu x")
+        );
+        assert!(matches!(args("three"), Err(ArgsError::Selection(_))));
+    }
+
+    #[test]
+    fn bench() {
+        assert_eq!(Ok(Command::Run(Args::default())), command(""));
+        assert_eq!(
+            Ok(Command::Bench(BenchArgs {
+                day: 15,
+                runs: 20,
+                warmup: 3,
+                baseline: Some("base.toml".to_string()),
+                ..BenchArgs::default()
+            })),
+            command("bench 15 --runs 20 --warmup 3 --baseline base.toml")
+        );
+        assert_eq!(
+            Ok(Command::Bench(BenchArgs {
+                day: 19,
+                save: Some("base.toml".to_string()),
+                threshold: 5,
+                ..BenchArgs::default()
+            })),
+            command("bench --save base.toml --threshold 5 19")
+        );
+        assert_eq!(
+            Err(ArgsError::MissingValue("bench".to_string())),
+            command("bench --runs 3")
+        );
+        assert_eq!(
+            Err(ArgsError::InvalidValue(
+                "--runs".to_string(),
+                "many".to_string()
+            )),
+            command("bench 3 --runs many")
+        );
+        assert_eq!(
+            Err(ArgsError::InvalidValue(
+                "--runs".to_string(),
+                "0".to_string()
+            )),
+            command("bench 3 --runs 0")
+        );
+    }
+
+    #[test]
+    fn fetch() {
+        assert_eq!(
+            Ok(Command::Fetch(FetchArgs { day: 16 })),
+            command("fetch 16")
+        );
+        assert_eq!(
+            Err(ArgsError::MissingValue("fetch".to_string())),
+            command("fetch")
+        );
+        assert_eq!(
+            Err(ArgsError::UnknownFlag("--force".to_string())),
+            command("fetch 16 --force")
+        );
+        assert!(matches!(command("fetch all"), Err(ArgsError::Selection(_))));
+    }
+
+    #[test]
+    fn log_level() {
+        assert_eq!(Level::Warn, level("3"));
+        assert_eq!(Level::Info, level("-v 3"));
+        assert_eq!(Level::Trace, level("bench 19 -vvv"));
+        assert_eq!(Level::Debug, level("-v 3 --verbose"));
+        assert_eq!(Level::Error, level("all -q --check"));
+        assert_eq!(
+            Ok(Command::Run(Args {
+                selection: 3..=3,
+                input: Some("-".to_string()),
+                ..Args::default()
+            })),
+            command("-v 3 --input -")
+        );
+        //The value of a flag is never a log flag
+        assert_eq!(Level::Warn, level("3 --input -vv"));
+        assert_eq!(
+            Ok(Command::Run(Args {
+                selection: 3..=3,
+                input: Some("-vv".to_string()),
+                ..Args::default()
+            })),
+            command("3 --input -vv")
+        );
+        assert_eq!(Level::Info, level("bench 3 --save -q -v"));
+    }
+}
@@ -1,17 +1,24 @@
-use std::io;
-use std::path::Path;
+use crate::error::{parse_lines, Result};
+use crate::solution::Solution;
 
-use crate::reader::read_lines;
+/// Day 1: Sonar Sweep
+pub struct Day1;
 
-pub fn main() {
-    match read_number("input/part1") {
-        Ok(numbers) => {
-            println!("Counted {}", count_increasing(&numbers));
-            println!("Counted {}", count_increasing_window(&numbers, 3));
-        }
-        Err(err) => {
-            eprintln!("Could not read file {:?}", err)
-        }
+impl Solution for Day1 {
+    type Input = Vec<i32>;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input, |l| Ok(l.trim().parse()?))
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(count_increasing(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(count_increasing_window(input, 3))
     }
 }
 
@@ -28,7 +35,7 @@ fn count_increasing(numbers: &[i32]) -> i32 {
 fn count_increasing_window(numbers: &[i32], window_size: usize) -> i32 {
     let mut count = 0;
     let mut last_window = 0;
-    for i in 0..numbers.len() - (window_size - 1) {
+    for i in 0..(numbers.len() + 1).saturating_sub(window_size) {
         let window = {
             let mut sum = 0;
             for j in 0..window_size {
@@ -44,17 +51,6 @@ fn count_increasing_window(numbers: &[i32], window_size: usize) -> i32 {
     count
 }
 
-fn read_number<P>(filename: P) -> io::Result<Vec<i32>>
-where
-    P: AsRef<Path>,
-{
-    let line_iter = read_lines(filename)?;
-
-    Ok(line_iter
-        .filter_map(|s| s.unwrap().parse::<i32>().ok())
-        .collect::<Vec<_>>())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,5 +60,20 @@ mod tests {
         let input = vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
 
         assert_eq!(count_increasing_window(&input, 3), 5);
+        assert_eq!(count_increasing_window(&input[..2], 3), 0);
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            "parse error at line 2: invalid number: invalid digit found in string",
+            Day1::parse(
+                "199
+2OO
+"
+            )
+            .unwrap_err()
+            .to_string()
+        );
     }
 }
@@ -1,34 +1,50 @@
 use std::collections::HashMap;
 
-use crate::reader::read_lines_filter_ok;
-
-pub fn main() {
-    println!(
-        "Day 10-1 {}",
-        syntax_error_score(read_lines_filter_ok("input/day10"))
-    );
-    println!(
-        "Day 10-2 {}",
-        incomplete_score(read_lines_filter_ok("input/day10"))
-    );
+use crate::error::{parse_lines, Error, Location, Result};
+use crate::solution::Solution;
+
+/// Day 10: Syntax Scoring
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+    type PartOne = u32;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input, |line| match line.find(|c| !"()[]{}<>".contains(c)) {
+            Some(column) => {
+                Err(Error::parse("expected only brackets").at(Location::new(1, column + 1)))
+            }
+            None => Ok(line.to_string()),
+        })
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(syntax_error_score(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        incomplete_score(input).ok_or(Error::no_solution("no line is incomplete"))
+    }
 }
 
-fn syntax_error_score(input: Vec<String>) -> u32 {
+fn syntax_error_score(input: &[String]) -> u32 {
     input
         .iter()
-        .filter_map(|s| find_corrupt(&s))
-        .filter_map(|c| score(c))
+        .filter_map(|s| find_corrupt(s))
+        .filter_map(score)
         .sum::<u32>()
 }
 
-fn incomplete_score(input: Vec<String>) -> u64 {
+fn incomplete_score(input: &[String]) -> Option<u64> {
     let mut all_scores: Vec<_> = input
         .iter()
-        .filter_map(|s| auto_complete(&s))
-        .map(|c| incomplete_score_single(c))
+        .filter_map(|s| auto_complete(s))
+        .map(incomplete_score_single)
         .collect();
     all_scores.sort();
-    all_scores[all_scores.len() / 2]
+    all_scores.get(all_scores.len() / 2).copied()
 }
 
 fn incomplete_score_single(line: Vec<char>) -> u64 {
@@ -100,7 +116,7 @@ mod test {
 
     #[test]
     fn part_one() {
-        let input = vec![
+        let input = [
             ("{([(<{}[<>[]}>{[]{[(<()>", '}'),
             ("[[<[([]))<([[{}[[()]]]", ')'),
             ("[{[{({}]{}}([{[{{{}}([]", ']'),
@@ -114,13 +130,13 @@ mod test {
 
         assert_eq!(
             26397u32,
-            syntax_error_score(input.iter().map(|(i, _)| i.to_string()).collect())
+            syntax_error_score(&input.iter().map(|(i, _)| i.to_string()).collect::<Vec<_>>())
         );
     }
 
     #[test]
     fn part_two() {
-        let input = vec![
+        let input = [
             ("[({(<(())[]>[[{[]{<()<>>", 288957),
             ("[(()[<>])]({[<{<<[]>>(", 5566),
             ("(((({<>}<{<{<>}{[]{[]{}", 1480781),
@@ -134,8 +150,22 @@ mod test {
             );
         }
         assert_eq!(
-            288957,
-            incomplete_score(input.iter().map(|(i, _)| i.to_string()).collect())
+            Some(288957),
+            incomplete_score(&input.iter().map(|(i, _)| i.to_string()).collect::<Vec<_>>())
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            "parse error at line 2, column 3: expected only brackets",
+            Day10::parse(
+                "[]
+(<a>)
+"
+            )
+            .unwrap_err()
+            .to_string()
         );
     }
 }
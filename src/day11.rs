@@ -1,106 +1,82 @@
-use crate::neighbors;
-use crate::neighbors::{neighbors, PosType};
-use crate::reader::parse_grid;
+use crate::error::{Error, Result};
+use crate::grid::{Edges, Grid, Neighborhood, Plane};
+use crate::reader::parse_digit_grid;
+use crate::solution::Solution;
 
-pub fn main() {
-    println!("Day 11 - 1: {}", run_real_input_part_one());
-    println!("Day 11 - 2: {}", run_real_input_part_two());
+/// Day 11: Dumbo Octopus
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Grid<u32>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_digit_grid(input)
+    }
+
+    fn part_one(grid: &Self::Input) -> Result<Self::PartOne> {
+        let mut octopuses = Plane::new(grid.clone(), Edges::Bounded);
+        let (glows, _) = simulate(StopCriterium::Days(100), &mut octopuses)?;
+        Ok(glows)
+    }
+
+    fn part_two(grid: &Self::Input) -> Result<Self::PartTwo> {
+        let mut octopuses = Plane::new(grid.clone(), Edges::Bounded);
+        let (_, days) = simulate(StopCriterium::Synchronized, &mut octopuses)?;
+        Ok(days)
+    }
 }
 
+/// The octopuses may never synchronize, stop looking after this many days
+const MAX_DAYS: u32 = 100_000;
+
 enum StopCriterium {
     Days(u32),
     Synchronized,
 }
 
-fn run_real_input_part_one() -> u32 {
-    let input = "1172728874
-    6751454281
-    2612343533
-    1884877511
-    7574346247
-    2117413745
-    7766736517
-    4331783444
-    4841215828
-    6857766273";
-    let ((row_size, col_size), parsed) = parse_grid(input);
-    let (glows, _) = simulate(
-        StopCriterium::Days(100),
-        parsed,
-        (row_size as u32, col_size as u32),
-    );
-    glows
-}
-
-fn run_real_input_part_two() -> u32 {
-    let input = "1172728874
-    6751454281
-    2612343533
-    1884877511
-    7574346247
-    2117413745
-    7766736517
-    4331783444
-    4841215828
-    6857766273";
-    let ((row_size, col_size), parsed) = parse_grid(input);
-    let (_, days) = simulate(
-        StopCriterium::Synchronized,
-        parsed,
-        (row_size as u32, col_size as u32),
-    );
-    days
-}
-
-fn simulate(
-    stop: StopCriterium,
-    mut grid: Vec<u32>,
-    (row_size, col_size): (u32, u32),
-) -> (u32, u32) {
-    let neighbor_map: Vec<Vec<usize>> = (0..grid.len())
-        .map(|i| PosType::from_index(i as u32, (row_size, col_size)))
-        .map(|((x, y), t)| {
-            neighbors((x, y), t)
-                .map(|(x, y)| (x as usize + y as usize * row_size as usize))
-                .collect::<Vec<usize>>()
-        })
-        .collect();
+/// Runs the octopuses until `stop`, returning the number of flashes and days. On an infinite
+/// plane the octopuses beyond the grid never flash, as they keep the energy they started with.
+fn simulate(stop: StopCriterium, octopuses: &mut Plane<u32>) -> Result<(u32, u32)> {
+    let neighbor_map = octopuses.grid().map_with_pos(|pos, _| {
+        octopuses
+            .neighbors(octopuses.plane_pos(pos), Neighborhood::Eight)
+            .filter_map(|n| octopuses.cell_pos(n))
+            .collect::<Vec<_>>()
+    });
+    let grid = octopuses.grid_mut();
     let mut all_glows = 0u32;
-    let mut glow_queue = Vec::<usize>::new();
+    let mut glow_queue = Vec::new();
     let mut day = 0u32;
     loop {
         glow_queue.clear();
         let mut glow_this_day = 0u32;
         //increment all elements in grid
-        for index in 0..grid.len() {
-            let count = grid.get_mut(index).unwrap();
+        for pos in grid.positions() {
+            let count = &mut grid[pos];
             *count += 1;
             if *count > 9 {
-                glow_queue.push(index);
-                let neighbors = neighbor_map.get(index).unwrap();
-                glow_queue.extend(neighbors);
+                glow_queue.extend(&neighbor_map[pos]);
                 glow_this_day += 1;
             }
         }
 
-        while !glow_queue.is_empty() {
-            let index = glow_queue.pop().unwrap();
-            let value = grid.get_mut(index).unwrap();
+        while let Some(pos) = glow_queue.pop() {
+            let value = &mut grid[pos];
             if *value > 9 {
                 continue;
             }
             *value += 1;
 
             if *value > 9 {
-                let neighbors = neighbor_map.get(index).unwrap();
-                glow_queue.extend(neighbors);
+                glow_queue.extend(&neighbor_map[pos]);
                 glow_this_day += 1;
             }
         }
-        for index in 0..grid.len() {
-            let count = grid.get_mut(index).unwrap();
-            if *count > 9 {
-                *count = 0;
+        for pos in grid.positions() {
+            if grid[pos] > 9 {
+                grid[pos] = 0;
             }
         }
         all_glows += glow_this_day;
@@ -112,34 +88,39 @@ fn simulate(
                 }
             }
             StopCriterium::Synchronized => {
-                if glow_this_day == row_size * col_size {
+                if glow_this_day as usize == grid.len() {
                     break;
                 }
+                if day >= MAX_DAYS {
+                    return Err(Error::no_solution(
+                        "the octopuses never flash at the same time",
+                    ));
+                }
             }
         }
     }
-    (all_glows, day)
+    Ok((all_glows, day))
 }
 
 #[cfg(test)]
 mod test {
-    use crate::day11::{
-        neighbors, run_real_input_part_one, run_real_input_part_two, simulate, PosType,
-        StopCriterium,
-    };
-    use crate::reader::parse_grid;
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
+
+    use super::*;
 
     #[test]
     fn count_neighbors() {
         let expected: [u32; 9] = [3, 5, 3, 5, 8, 5, 3, 5, 3];
-        let neighbors = (0u32..9)
-            .map(|i| PosType::from_index(i, (3, 3)))
-            .map(|(p, t)| neighbors(p, t))
-            .flatten();
+        let grid = Grid::from_fn(3, 3, |_| 0);
+        let neighbors = grid
+            .positions()
+            .flat_map(|p| grid.neighbors(p, Neighborhood::Eight));
 
         let mut result: [u32; 9] = [0u32; 9];
-        for (x, y) in neighbors {
-            result[(x + y * 3) as usize] += 1;
+        for pos in neighbors {
+            result[grid.index_of(pos).unwrap()] += 1;
         }
 
         assert_eq!(expected, result);
@@ -152,56 +133,60 @@ mod test {
 19191
 19991
 11111";
-        let ((row_size, col_size), parsed) = parse_grid(input);
-        let (glows, _) = simulate(
-            StopCriterium::Days(1),
-            parsed.clone(),
-            (row_size as u32, col_size as u32),
-        );
+        let mut parsed = Plane::new(parse_digit_grid(input).unwrap(), Edges::Bounded);
+        let (glows, _) = simulate(StopCriterium::Days(1), &mut parsed).unwrap();
         assert_eq!(9, glows);
     }
 
     #[test]
     fn part_one() {
-        let input = "5483143223
-2745854711
-5264556173
-6141336146
-6357385478
-4167524645
-2176841721
-6882881134
-4846848554
-5283751526";
-
-        let ((row_size, col_size), parsed) = parse_grid(input);
+        let input = example(11, "example");
+
+        let parsed = Plane::new(parse_digit_grid(&input).unwrap(), Edges::Bounded);
         assert_eq!(
             0,
-            simulate(
-                StopCriterium::Days(1),
-                parsed.clone(),
-                (row_size as u32, col_size as u32)
-            )
-            .0
+            simulate(StopCriterium::Days(1), &mut parsed.clone())
+                .unwrap()
+                .0
         );
 
         assert_eq!(
             1656,
-            simulate(
-                StopCriterium::Days(100),
-                parsed,
-                (row_size as u32, col_size as u32)
-            )
-            .0
+            simulate(StopCriterium::Days(100), &mut parsed.clone())
+                .unwrap()
+                .0
         );
     }
 
+    #[test]
+    fn topologies() {
+        let grid = parse_digit_grid("9000\n0000\n0000\n0000").unwrap();
+        let day_one = |edges| {
+            let mut octopuses = Plane::new(grid.clone(), edges);
+            simulate(StopCriterium::Days(1), &mut octopuses).unwrap();
+            octopuses.grid().to_string()
+        };
+        assert_eq!("0211\n2211\n1111\n1111", day_one(Edges::Bounded));
+        assert_eq!("0212\n2212\n1111\n2212", day_one(Edges::Wrapping));
+        assert_eq!("0211\n2211\n1111\n1111", day_one(Edges::Infinite(0)));
+    }
+
     #[test]
     fn part_one_real_input() {
-        assert_eq!(1644, run_real_input_part_one());
+        assert_eq!(
+            recorded(11, 1),
+            Day11::part_one(&Day11::parse(&real_input(11)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
     #[test]
     fn part_two_real_input() {
-        assert_eq!(229, run_real_input_part_two());
+        assert_eq!(
+            recorded(11, 2),
+            Day11::part_two(&Day11::parse(&real_input(11)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 }
@@ -1,6 +1,31 @@
 use std::collections::HashMap;
 
-use crate::reader::split_lines;
+use nom::character::complete::{alpha1, char};
+use nom::sequence::separated_pair;
+
+use crate::error::{parse_all, Error, Result};
+use crate::solution::Solution;
+
+/// Day 12: Passage Pathing
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = EdgeMap;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_edges(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(find_paths(input, false)?.len())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(find_paths(input, true)?.len())
+    }
+}
 
 type Node = String;
 type NodeList = Vec<String>;
@@ -29,24 +54,34 @@ fn append(new: &str, path: &Path) -> Path {
     (p, d)
 }
 
-fn find_paths(input: &str, allow_second_small: bool) -> Vec<Path> {
-    let lines = split_lines(input);
+fn parse_edges(input: &str) -> Result<EdgeMap> {
     let mut edges = EdgeMap::new();
-    for line in lines {
-        parse(&line, &mut edges);
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if !line.is_empty() {
+            parse(line, &mut edges).map_err(|e| e.on_line(i + 1))?;
+        }
+    }
+    if !edges.contains_key("start") || !edges.contains_key("end") {
+        return Err(Error::invalid("the caves need a start and an end"));
     }
-    let mut routes: Vec<Path> = edges
-        .get("start")
-        .unwrap()
+    Ok(edges)
+}
+
+fn find_paths(edges: &EdgeMap, allow_second_small: bool) -> Result<Vec<Path>> {
+    let outgoing = |node: &str| {
+        edges
+            .get(node)
+            .ok_or_else(|| Error::invalid(format!("cave {} has no passages", node)))
+    };
+    let mut routes: Vec<Path> = outgoing("start")?
         .iter()
         .map(|n| (vec!["start".to_string(), n.to_string()], false))
         .collect();
     let mut finished_routes = Vec::<Path>::new();
-    while !routes.is_empty() {
-        let cur_path = routes.pop().unwrap();
-        let cur_node = cur_path.0.last().unwrap();
-        let outgoing = edges.get(cur_node).unwrap();
-        for next_node in outgoing.iter() {
+    while let Some(cur_path) = routes.pop() {
+        let cur_node = cur_path.0.last().map_or("start", String::as_str);
+        for next_node in outgoing(cur_node)?.iter() {
             let node_type = node_type(next_node);
             match node_type {
                 NodeType::Start => {}
@@ -58,7 +93,7 @@ fn find_paths(input: &str, allow_second_small: bool) -> Vec<Path> {
                     let (p, has_second) = &cur_path;
                     if allow_second_small {
                         let in_cur_path = p.contains(next_node);
-                        let do_insert = !in_cur_path || (in_cur_path && !*has_second);
+                        let do_insert = !in_cur_path || !*has_second;
                         if do_insert {
                             let (p, _) = append(next_node, &cur_path);
                             routes.push((p, in_cur_path || *has_second));
@@ -78,36 +113,41 @@ fn find_paths(input: &str, allow_second_small: bool) -> Vec<Path> {
         }
     }
 
-    finished_routes
+    Ok(finished_routes)
 }
 
-fn parse(input: &str, edges: &mut EdgeMap) {
-    let mut matches = input.split("-");
-    let node_1 = matches.next().unwrap().to_string();
-    let node_2 = matches.next().unwrap().to_string();
+fn parse(input: &str, edges: &mut EdgeMap) -> Result<()> {
+    //"start-A"
+    let (node_1, node_2) = parse_all(input, separated_pair(alpha1, char('-'), alpha1))?;
+    if let (NodeType::Big, NodeType::Big) = (node_type(node_1), node_type(node_2)) {
+        //Paths could go back and forth between these forever
+        return Err(Error::invalid("two big caves can't be connected"));
+    }
+    let (node_1, node_2) = (node_1.to_string(), node_2.to_string());
     {
-        let list_1 = edges
-            .entry(node_1.clone())
-            .or_insert_with(|| NodeList::new());
+        let list_1 = edges.entry(node_1.clone()).or_default();
         list_1.push(node_2.to_string());
     }
     {
-        let list_2 = edges
-            .entry(node_2.clone())
-            .or_insert_with(|| NodeList::new());
+        let list_2 = edges.entry(node_2.clone()).or_default();
         list_2.push(node_1.to_string());
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
+
     use super::*;
 
     #[test]
     fn parse_single() {
         let input = "start-A";
         let mut edges = EdgeMap::new();
-        parse(input, &mut edges);
+        parse(input, &mut edges).unwrap();
 
         assert_eq!(vec!["A".to_string()], *edges.get("start").unwrap());
         assert_eq!(vec!["start".to_string()], *edges.get("A").unwrap());
@@ -115,79 +155,47 @@ mod test {
 
     #[test]
     fn part_one_small() {
-        let input = "start-A
-start-b
-A-c
-A-b
-b-d
-A-end
-b-end";
-        assert_eq!(10, find_paths(input, false).len());
+        let input = example(12, "small");
+        assert_eq!(
+            10,
+            find_paths(&parse_edges(&input).unwrap(), false)
+                .unwrap()
+                .len()
+        );
     }
 
     #[test]
     fn part_one() {
-        let input = "FK-gc
-gc-start
-gc-dw
-sp-FN
-dw-end
-FK-start
-dw-gn
-AN-gn
-yh-gn
-yh-start
-sp-AN
-ik-dw
-FK-dw
-end-sp
-yh-FK
-gc-gn
-AN-end
-dw-AN
-gn-sp
-gn-FK
-sp-FK
-yh-gc";
-        assert_eq!(3713, find_paths(input, false).len());
+        assert_eq!(
+            recorded(12, 1),
+            Day12::part_one(&Day12::parse(&real_input(12)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 
     #[test]
     fn part_two_small() {
-        let input = "start-A
-start-b
-A-c
-A-b
-b-d
-A-end
-b-end";
-        assert_eq!(36, find_paths(input, true).len());
+        let input = example(12, "small");
+        assert_eq!(
+            36,
+            find_paths(&parse_edges(&input).unwrap(), true)
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            "invalid input at line 2: two big caves can't be connected",
+            parse_edges("start-A\nA-B\nB-end").unwrap_err().to_string()
+        );
     }
 
     #[test]
     fn part_two() {
-        let input = "FK-gc
-gc-start
-gc-dw
-sp-FN
-dw-end
-FK-start
-dw-gn
-AN-gn
-yh-gn
-yh-start
-sp-AN
-ik-dw
-FK-dw
-end-sp
-yh-FK
-gc-gn
-AN-end
-dw-AN
-gn-sp
-gn-FK
-sp-FK
-yh-gc";
-        assert_eq!(91292, find_paths(input, true).len());
+        assert_eq!(
+            recorded(12, 2),
+            Day12::part_two(&Day12::parse(&real_input(12)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 }
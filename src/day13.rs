@@ -1,11 +1,42 @@
 use std::cmp::Ordering;
 
-use crate::reader::split_lines;
+use nom::branch::alt;
+use nom::combinator::map;
+use nom::IResult;
+
+use crate::error::{parse_all, parse_lines, Error, Result};
+use crate::parsers::{coordinates, integer, key_value};
+use crate::reader::paragraphs;
+use crate::solution::Solution;
+
+/// Day 13: Transparent Origami
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = (Vec<Point>, Vec<Fold>);
+    type PartOne = usize;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse(input)
+    }
+
+    fn part_one((points, folds): &Self::Input) -> Result<Self::PartOne> {
+        Ok(fold(points.clone(), folds[0]).len())
+    }
+
+    fn part_two((points, folds): &Self::Input) -> Result<Self::PartTwo> {
+        let folded = folds
+            .iter()
+            .fold(points.clone(), |points, f| fold(points, *f));
+        Ok(render(&folded))
+    }
+}
 
 type Point = (i32, i32);
 
 #[derive(Debug, Copy, Clone)]
-enum Fold {
+pub enum Fold {
     X(i32),
     Y(i32),
 }
@@ -18,15 +49,17 @@ fn sort_point((x1, y1): &Point, (x2, y2): &Point) -> Ordering {
     }
 }
 
-fn print(mut points: Vec<Point>) {
-    let (size_x, size_y) = grid_size(&points);
+fn render(points: &[Point]) -> String {
+    let (size_x, size_y) = grid_size(points);
+    let mut points = points.to_vec();
     points.sort_by(sort_point);
     let mut iter = points.iter();
     let mut cur_point = *iter.next().unwrap();
+    let mut result = String::new();
     for y in 0..size_y {
         for x in 0..size_x {
             let has_point = (x, y) == cur_point;
-            print!("{}", if has_point { "#" } else { "." });
+            result.push(if has_point { '#' } else { '.' });
             if has_point {
                 if let Some(p) = iter.next() {
                     //If we run out of iter we just keep the old point which will never match
@@ -34,11 +67,12 @@ fn print(mut points: Vec<Point>) {
                 }
             }
         }
-        println!()
+        result.push('\n');
     }
+    result
 }
 
-fn grid_size(points: &Vec<Point>) -> (i32, i32) {
+fn grid_size(points: &[Point]) -> (i32, i32) {
     let (max_x, _) = *points.iter().max_by_key(|(x, _)| x).unwrap();
     let (_, max_y) = *points.iter().max_by_key(|(_, y)| y).unwrap();
     (max_x + 1, max_y + 1)
@@ -72,50 +106,41 @@ fn fold(points: Vec<Point>, fold: Fold) -> Vec<Point> {
     points
 }
 
-fn parse_pos(input: &String) -> Option<Point> {
-    let mut chunks = input.split(',');
-    let x = chunks.next();
-    let y = chunks.next();
-    if x.is_none() || y.is_none() {
-        return None;
-    }
-
-    let x = x.unwrap().parse::<i32>().ok();
-    let y = y.unwrap().parse::<i32>().ok();
-    if x.is_none() || y.is_none() {
-        return None;
-    }
-    Some((x.unwrap(), y.unwrap()))
+//"6,10"
+fn dot(input: &str) -> IResult<&str, Point> {
+    map(coordinates::<u32>, |(x, y)| (x as i32, y as i32))(input)
 }
 
-fn parse_fold(input: &String) -> Option<Fold> {
-    let mut chunks = input.split('=');
-    let dir = chunks.next();
-    let length = chunks.next();
-    if dir.is_none() || length.is_none() {
-        return None;
-    }
-
-    let dir_is_x = dir.unwrap().contains('x');
-    let length = length.unwrap().parse::<i32>().ok();
-    if length.is_none() {
-        return None;
-    }
-    match dir_is_x {
-        true => Some(Fold::X(length.unwrap())),
-        false => Some(Fold::Y(length.unwrap())),
-    }
+//"fold along y=7"
+fn fold_line(input: &str) -> IResult<&str, Fold> {
+    let length = || map(integer::<u32>, |l| l as i32);
+    alt((
+        map(key_value("fold along x", length()), Fold::X),
+        map(key_value("fold along y", length()), Fold::Y),
+    ))(input)
 }
 
-fn parse(lines: Vec<String>) -> (Vec<Point>, Vec<Fold>) {
-    let positions: Vec<_> = lines.iter().filter_map(parse_pos).collect();
-    let folds = lines.iter().filter_map(parse_fold).collect();
-    (positions, folds)
+fn parse(input: &str) -> Result<(Vec<Point>, Vec<Fold>)> {
+    let mut sections = paragraphs(input);
+    let (dots, folds) = match (sections.next(), sections.next(), sections.next()) {
+        (Some(dots), Some(folds), None) => (dots, folds),
+        (_, _, Some(extra)) => {
+            return Err(Error::invalid("expected nothing after the folds").on_line(extra.line))
+        }
+        _ => return Err(Error::invalid("expected both dots and folds")),
+    };
+    let positions =
+        parse_lines(dots.text, |line| parse_all(line, dot)).map_err(|e| e.on_line(dots.line))?;
+    let folds = parse_lines(folds.text, |line| parse_all(line, fold_line))
+        .map_err(|e| e.on_line(folds.line))?;
+    Ok((positions, folds))
 }
 
 #[cfg(test)]
 mod test {
-    use crate::reader::read_lines_filter_ok;
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
 
     use super::*;
 
@@ -135,28 +160,8 @@ mod test {
 
     #[test]
     fn part_one_small() {
-        let input = "6,10
-0,14
-9,10
-0,3
-10,4
-4,11
-6,0
-6,12
-4,1
-0,13
-10,12
-3,4
-3,0
-8,4
-1,10
-2,14
-8,10
-9,0
-
-fold along y=7
-fold along x=5";
-        let (pos, folds) = parse(split_lines(input));
+        let input = example(13, "example");
+        let (pos, folds) = parse(&input).unwrap();
         assert_eq!(18, pos.len());
         assert_eq!(2, folds.len());
         let folded = fold(pos, folds[0]);
@@ -165,18 +170,37 @@ fold along x=5";
 
     #[test]
     fn part_one_real() {
-        let (pos, folds) = parse(read_lines_filter_ok("input/day13"));
-        let folded = fold(pos, folds[0]);
-        assert_eq!(751, folded.len())
+        assert_eq!(
+            recorded(13, 1),
+            Day13::part_one(&Day13::parse(&real_input(13)).unwrap())
+                .unwrap()
+                .to_string()
+        )
     }
 
     #[test]
     fn part_two_real() {
-        let (mut pos, folds) = parse(read_lines_filter_ok("input/day13"));
+        assert_eq!(
+            recorded(13, 2),
+            Day13::part_two(&Day13::parse(&real_input(13)).unwrap()).unwrap()
+        );
+    }
 
-        for f in folds {
-            pos = fold(pos, f);
-        }
-        print(pos);
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            "parse error at line 3, column 1: unexpected \"fold along z=3\" (Tag)",
+            parse("6,10\n\nfold along z=3").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid input: expected both dots and folds",
+            parse("6,10\nfold along y=3").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid input at line 5: expected nothing after the folds",
+            parse("6,10\n\nfold along y=3\n\n1,2")
+                .unwrap_err()
+                .to_string()
+        );
     }
 }
@@ -1,23 +1,58 @@
 use std::collections::HashMap;
 
-use crate::reader::read_lines_filter_ok;
+use nom::character::complete::satisfy;
+use nom::sequence::pair;
+use nom::IResult;
+
+use crate::error::{parse_all, parse_lines, Error, Result};
+use crate::parsers::arrow;
+use crate::solution::Solution;
 
 type InstructionMap = HashMap<(char, char), char>;
 
-pub fn main() {
-    let lines = read_lines_filter_ok("input/day14");
-    let (start, instructions) = parse_lines(&lines);
-    println!("Day 14-2 {}", expansion_score(&start, &instructions, 40));
+/// Day 14: Extended Polymerization
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = (String, InstructionMap);
+    type PartOne = u128;
+    type PartTwo = u128;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let trimmed = input.trim_start();
+        //Rules start on the line after the template, past any blank lines before it
+        let first_rule = input[..input.len() - trimmed.len()].matches('\n').count() + 2;
+        let (template, rules) = trimmed.split_once('\n').unwrap_or((trimmed, ""));
+        let template = template.trim();
+        if template.is_empty() {
+            return Err(Error::invalid("the polymer template is empty"));
+        }
+        let rules =
+            parse_lines(rules, |line| parse_all(line, rule)).map_err(|e| e.on_line(first_rule))?;
+        Ok((template.to_string(), rules.into_iter().collect()))
+    }
+
+    fn part_one((start, instructions): &Self::Input) -> Result<Self::PartOne> {
+        expansion_score(start, instructions, 10)
+    }
+
+    fn part_two((start, instructions): &Self::Input) -> Result<Self::PartTwo> {
+        expansion_score(start, instructions, 40)
+    }
 }
 
-fn expansion_score(input: &String, instructions: &InstructionMap, iterations: u32) -> u128 {
-    let counts = expand(input, instructions, iterations);
+fn expansion_score(input: &str, instructions: &InstructionMap, iterations: u32) -> Result<u128> {
+    let counts = expand(input, instructions, iterations)?;
     let mut vec: Vec<u128> = counts.into_values().collect();
     vec.sort();
-    vec.last().unwrap() - vec[0]
+    Ok(vec.last().unwrap() - vec[0])
 }
 
-fn expand(input: &String, instructions: &InstructionMap, iterations: u32) -> HashMap<char, u128> {
+fn expand(
+    input: &str,
+    instructions: &InstructionMap,
+    iterations: u32,
+) -> Result<HashMap<char, u128>> {
     type InvocationMap = HashMap<(char, char), u64>;
     let mut counts = HashMap::<char, u128>::new();
     fn add_invocation(key: (char, char), map: &mut InvocationMap, count: u64) {
@@ -38,7 +73,9 @@ fn expand(input: &String, instructions: &InstructionMap, iterations: u32) -> Has
     for _ in 0..iterations {
         let mut next_invocations = InvocationMap::new();
         for ((c0, c1), count) in invocations_next_round {
-            let c = *instructions.get(&(c0, c1)).unwrap();
+            let c = *instructions
+                .get(&(c0, c1))
+                .ok_or_else(|| Error::invalid(format!("no rule for the pair {}{}", c0, c1)))?;
             *counts.entry(c).or_insert(0) += count as u128;
             add_invocation((c0, c), &mut next_invocations, count);
             add_invocation((c, c1), &mut next_invocations, count);
@@ -46,70 +83,71 @@ fn expand(input: &String, instructions: &InstructionMap, iterations: u32) -> Has
         invocations_next_round = next_invocations;
     }
 
-    counts
+    Ok(counts)
+}
+
+fn element(input: &str) -> IResult<&str, char> {
+    satisfy(|c| c.is_ascii_alphabetic())(input)
 }
 
-fn parse_lines(lines: &Vec<String>) -> (String, InstructionMap) {
-    let input = lines[0].to_string();
-    let map: InstructionMap = lines
-        .iter()
-        .skip(2)
-        .map(|l| {
-            let mut chunks = l.split("->");
-            let mut in_chars = chunks.next().unwrap().trim().chars();
-            let pair = (in_chars.next().unwrap(), in_chars.next().unwrap());
-            let output = chunks.next().unwrap().trim().chars().next().unwrap();
-            (pair, output)
-        })
-        .collect();
-    (input, map)
+//"CH -> B"
+fn rule(input: &str) -> IResult<&str, ((char, char), char)> {
+    arrow(pair(element, element), element)(input)
 }
 
 #[cfg(test)]
 mod test {
-    use crate::reader::{read_lines_filter_ok, split_lines};
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
 
     use super::*;
 
     #[test]
     fn part_one_small() {
-        let input = "NNCB
-
-CH -> B
-HH -> N
-CB -> H
-NH -> C
-HB -> C
-HC -> B
-HN -> C
-NN -> C
-BH -> H
-NC -> B
-NB -> B
-BN -> B
-BB -> N
-BC -> B
-CC -> N
-CN -> C";
-        let lines = split_lines(input);
-        let (start, instructions) = parse_lines(&lines);
+        let input = example(14, "example");
+        let (start, instructions) = Day14::parse(&input).unwrap();
 
         assert_eq!("NNCB", start);
         assert_eq!(16, instructions.len());
-        assert_eq!(1588, expansion_score(&start, &instructions, 10));
+        assert_eq!(Ok(1588), expansion_score(&start, &instructions, 10));
+        assert_eq!(
+            "invalid input: no rule for the pair NC",
+            expansion_score("NC", &HashMap::new(), 1)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "parse error at line 4, column 3: unexpected end of line (Tag)",
+            Day14::parse("NNCB\n\nCH -> B\nHH\n")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "parse error at line 6, column 3: unexpected end of line (Tag)",
+            Day14::parse("\n\nNNCB\n\nCH -> B\nHH\n")
+                .unwrap_err()
+                .to_string()
+        );
     }
 
     #[test]
     fn part_one() {
-        let lines = read_lines_filter_ok("input/day14");
-        let (start, instructions) = parse_lines(&lines);
-        assert_eq!(3587, expansion_score(&start, &instructions, 10));
+        assert_eq!(
+            recorded(14, 1),
+            Day14::part_one(&Day14::parse(&real_input(14)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 
     #[test]
     fn part_two() {
-        let lines = read_lines_filter_ok("input/day14");
-        let (start, instructions) = parse_lines(&lines);
-        assert_eq!(3906445077999, expansion_score(&start, &instructions, 40));
+        assert_eq!(
+            recorded(14, 2),
+            Day14::part_two(&Day14::parse(&real_input(14)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 }
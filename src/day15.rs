@@ -1,60 +1,57 @@
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
-use crate::neighbors::{neighbors_straight, PosType};
-use crate::reader::{parse_grid_lines, read_lines_filter_ok};
+use crate::error::{Error, Result};
+use crate::grid::{Grid, Neighborhood, Pos};
+use crate::reader::parse_digit_grid;
+use crate::solution::Solution;
 
-pub fn main() {
-    println!(
-        "Day 15-1 {}",
-        find_path_lines(read_lines_filter_ok("input/day15")).unwrap()
-    );
-    let ((row, col), grid) = parse_grid_lines(read_lines_filter_ok("input/day15"));
-    let scale = 5;
-    let grid = expand(grid, (row, col), scale);
-    println!(
-        "Day 15-2 {}",
-        find_path((row * scale, col * scale), grid).unwrap()
-    );
-}
+/// Day 15: Chiton
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Grid<u32>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_digit_grid(input)
+    }
+
+    fn part_one(grid: &Self::Input) -> Result<Self::PartOne> {
+        find_path(grid).ok_or(Error::no_solution("there is no path to the exit"))
+    }
 
-fn expand(grid: Vec<u32>, (raw_row, raw_col): (usize, usize), scale: usize) -> Vec<u32> {
-    let scaled_row = raw_row * scale;
-    let mut result = vec![0; grid.len() * scale * scale];
-    for index in 0..result.len() {
-        let x = index % scaled_row;
-        let y = index / scaled_row;
-        let x_unscaled = x % raw_row;
-        let y_unscaled = y % raw_col;
+    fn part_two(grid: &Self::Input) -> Result<Self::PartTwo> {
+        find_path(&expand(grid, 5)).ok_or(Error::no_solution("there is no path to the exit"))
+    }
+}
 
-        let raw_cost = grid[x_unscaled + y_unscaled * raw_row];
-        let increment = (x / raw_row + y / raw_col) as u32;
+fn expand(grid: &Grid<u32>, scale: usize) -> Grid<u32> {
+    let (raw_row, raw_col) = (grid.width(), grid.height());
+    Grid::from_fn(raw_row * scale, raw_col * scale, |pos| {
+        let raw_cost = grid[Pos::new(pos.x % raw_row, pos.y % raw_col)];
+        let increment = (pos.x / raw_row + pos.y / raw_col) as u32;
 
-        result[index] = if raw_cost + increment > 9 {
+        if raw_cost + increment > 9 {
             (raw_cost + increment) % 9
         } else {
             raw_cost + increment
         }
-    }
-    result
-}
-
-fn find_path_lines(lines: Vec<String>) -> Option<usize> {
-    let (size, grid) = parse_grid_lines(lines);
-    find_path(size, grid)
+    })
 }
 
-fn find_path((row_size, col_size): (usize, usize), grid: Vec<u32>) -> Option<usize> {
-    let adj_list: Vec<_> = (0..grid.len())
-        .map(|i| PosType::from_index(i as u32, (row_size as u32, col_size as u32)))
-        .map(|(p, t)| {
-            neighbors_straight(p, t)
-                .map(|(x, y)| (x as usize + y as usize * row_size) as usize)
+fn find_path(grid: &Grid<u32>) -> Option<usize> {
+    let adj_list: Vec<_> = grid
+        .positions()
+        .map(|p| {
+            grid.neighbors(p, Neighborhood::Four)
+                .filter_map(|n| grid.index_of(n))
                 .collect::<Vec<_>>()
         })
         .collect();
-    let goal = grid.len() - 1;
-    path(adj_list, grid, goal)
+    let goal = grid.len().checked_sub(1)?;
+    path(adj_list, grid.cells(), goal)
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -81,7 +78,7 @@ impl Ord for State {
     }
 }
 
-fn path(adj_list: Vec<Vec<usize>>, cost_map: Vec<u32>, end: usize) -> Option<usize> {
+fn path(adj_list: Vec<Vec<usize>>, cost_map: &[u32], end: usize) -> Option<usize> {
     let mut dist: Vec<_> = (0..adj_list.len()).map(|_| usize::MAX).collect();
     let mut heap = BinaryHeap::new();
     heap.push(State {
@@ -113,184 +110,70 @@ fn path(adj_list: Vec<Vec<usize>>, cost_map: Vec<u32>, end: usize) -> Option<usi
 
 #[cfg(test)]
 mod test {
-    use crate::reader::{parse_grid, split_lines};
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
 
     use super::*;
 
+    fn find_path_str(input: &str) -> Option<usize> {
+        find_path(&parse_digit_grid(input).unwrap())
+    }
+
     #[test]
     fn part_one_small() {
-        let input = "1163751742
-1381373672
-2136511328
-3694931569
-7463417111
-1319128137
-1359912421
-3125421639
-1293138521
-2311944581";
+        let input = example(15, "example");
 
-        let lines = split_lines(input);
-
-        assert_eq!(Some(40), find_path_lines(lines));
+        assert_eq!(Some(40), find_path_str(&input));
     }
 
     #[test]
     fn part_one() {
         assert_eq!(
-            Some(720),
-            find_path_lines(read_lines_filter_ok("input/day15"))
+            recorded(15, 1),
+            Day15::part_one(&Day15::parse(&real_input(15)).unwrap())
+                .unwrap()
+                .to_string()
         );
     }
 
     #[test]
     fn part_two_scale_small() {
-        let (_, input) = parse_grid("8");
-        let (_, expected) = parse_grid(
+        let input = parse_digit_grid("8").unwrap();
+        let expected = parse_digit_grid(
             "89123
 91234
 12345
 23456
 34567",
-        );
-        assert_eq!(expected, expand(input, (1, 1), 5));
+        )
+        .unwrap();
+        assert_eq!(expected, expand(&input, 5));
     }
 
     #[test]
     fn part_two_scale() {
-        let ((row, col), input_grid) = parse_grid(
-            "1163751742
-1381373672
-2136511328
-3694931569
-7463417111
-1319128137
-1359912421
-3125421639
-1293138521
-2311944581",
-        );
+        let input_grid = parse_digit_grid(&example(15, "example"));
 
-        let (_, expected) = parse_grid(
-            "11637517422274862853338597396444961841755517295286
-13813736722492484783351359589446246169155735727126
-21365113283247622439435873354154698446526571955763
-36949315694715142671582625378269373648937148475914
-74634171118574528222968563933317967414442817852555
-13191281372421239248353234135946434524615754563572
-13599124212461123532357223464346833457545794456865
-31254216394236532741534764385264587549637569865174
-12931385212314249632342535174345364628545647573965
-23119445813422155692453326671356443778246755488935
-22748628533385973964449618417555172952866628316397
-24924847833513595894462461691557357271266846838237
-32476224394358733541546984465265719557637682166874
-47151426715826253782693736489371484759148259586125
-85745282229685639333179674144428178525553928963666
-24212392483532341359464345246157545635726865674683
-24611235323572234643468334575457944568656815567976
-42365327415347643852645875496375698651748671976285
-23142496323425351743453646285456475739656758684176
-34221556924533266713564437782467554889357866599146
-33859739644496184175551729528666283163977739427418
-35135958944624616915573572712668468382377957949348
-43587335415469844652657195576376821668748793277985
-58262537826937364893714847591482595861259361697236
-96856393331796741444281785255539289636664139174777
-35323413594643452461575456357268656746837976785794
-35722346434683345754579445686568155679767926678187
-53476438526458754963756986517486719762859782187396
-34253517434536462854564757396567586841767869795287
-45332667135644377824675548893578665991468977611257
-44961841755517295286662831639777394274188841538529
-46246169155735727126684683823779579493488168151459
-54698446526571955763768216687487932779859814388196
-69373648937148475914825958612593616972361472718347
-17967414442817852555392896366641391747775241285888
-46434524615754563572686567468379767857948187896815
-46833457545794456865681556797679266781878137789298
-64587549637569865174867197628597821873961893298417
-45364628545647573965675868417678697952878971816398
-56443778246755488935786659914689776112579188722368
-55172952866628316397773942741888415385299952649631
-57357271266846838237795794934881681514599279262561
-65719557637682166874879327798598143881961925499217
-71484759148259586125936169723614727183472583829458
-28178525553928963666413917477752412858886352396999
-57545635726865674683797678579481878968159298917926
-57944568656815567976792667818781377892989248891319
-75698651748671976285978218739618932984172914319528
-56475739656758684176786979528789718163989182927419
-67554889357866599146897761125791887223681299833479",
-        );
-        let scale = 5;
-        let grid = expand(input_grid, (row, col), scale);
+        let expected = parse_digit_grid(&example(15, "expanded")).unwrap();
+        let grid = expand(&input_grid.unwrap(), 5);
         assert_eq!(expected, grid);
     }
 
     #[test]
     fn part_two_small() {
-        let input = "11637517422274862853338597396444961841755517295286
-13813736722492484783351359589446246169155735727126
-21365113283247622439435873354154698446526571955763
-36949315694715142671582625378269373648937148475914
-74634171118574528222968563933317967414442817852555
-13191281372421239248353234135946434524615754563572
-13599124212461123532357223464346833457545794456865
-31254216394236532741534764385264587549637569865174
-12931385212314249632342535174345364628545647573965
-23119445813422155692453326671356443778246755488935
-22748628533385973964449618417555172952866628316397
-24924847833513595894462461691557357271266846838237
-32476224394358733541546984465265719557637682166874
-47151426715826253782693736489371484759148259586125
-85745282229685639333179674144428178525553928963666
-24212392483532341359464345246157545635726865674683
-24611235323572234643468334575457944568656815567976
-42365327415347643852645875496375698651748671976285
-23142496323425351743453646285456475739656758684176
-34221556924533266713564437782467554889357866599146
-33859739644496184175551729528666283163977739427418
-35135958944624616915573572712668468382377957949348
-43587335415469844652657195576376821668748793277985
-58262537826937364893714847591482595861259361697236
-96856393331796741444281785255539289636664139174777
-35323413594643452461575456357268656746837976785794
-35722346434683345754579445686568155679767926678187
-53476438526458754963756986517486719762859782187396
-34253517434536462854564757396567586841767869795287
-45332667135644377824675548893578665991468977611257
-44961841755517295286662831639777394274188841538529
-46246169155735727126684683823779579493488168151459
-54698446526571955763768216687487932779859814388196
-69373648937148475914825958612593616972361472718347
-17967414442817852555392896366641391747775241285888
-46434524615754563572686567468379767857948187896815
-46833457545794456865681556797679266781878137789298
-64587549637569865174867197628597821873961893298417
-45364628545647573965675868417678697952878971816398
-56443778246755488935786659914689776112579188722368
-55172952866628316397773942741888415385299952649631
-57357271266846838237795794934881681514599279262561
-65719557637682166874879327798598143881961925499217
-71484759148259586125936169723614727183472583829458
-28178525553928963666413917477752412858886352396999
-57545635726865674683797678579481878968159298917926
-57944568656815567976792667818781377892989248891319
-75698651748671976285978218739618932984172914319528
-56475739656758684176786979528789718163989182927419
-67554889357866599146897761125791887223681299833479";
-
-        let lines = split_lines(input);
+        let input = example(15, "expanded");
 
-        assert_eq!(Some(315), find_path_lines(lines));
+        assert_eq!(Some(315), find_path_str(&input));
     }
 
     #[test]
     fn part_two() {
-        let ((row, col), grid) = parse_grid_lines(read_lines_filter_ok("input/day15"));
-        let scale = 5;
-        let grid = expand(grid, (row, col), scale);
-        assert_eq!(Some(3025), find_path((row * scale, col * scale), grid));
+        assert_eq!(
+            recorded(15, 2),
+            Day15::part_two(&Day15::parse(&real_input(15)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 }
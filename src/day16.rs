@@ -1,206 +1,565 @@
-use std::collections::HashMap;
+use std::fmt;
 
-use lazy_static::lazy_static;
 use nom::branch::alt;
-use nom::bytes::complete::{tag, take};
-use nom::combinator::map_res;
-use nom::multi::{count, many1, many_till};
-use nom::sequence::{preceded, tuple};
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, char, multispace0, multispace1, u64};
+use nom::combinator::{map, map_opt, opt, value};
+use nom::multi::{many0, separated_list0, separated_list1};
+use nom::sequence::{delimited, pair, preceded, terminated};
 use nom::IResult;
 
+use crate::bits::{hex_to_bytes, BitReader, BitWriter};
 use crate::day16::PacketPayload::Literal;
+use crate::error::{parse_all, Error, Location, Result};
+use crate::solution::Solution;
 
-lazy_static! {
-    static ref HEX_MAP: HashMap<char, &'static str> = HashMap::from([
-        ('0', "0000"),
-        ('1', "0001"),
-        ('2', "0010"),
-        ('3', "0011"),
-        ('4', "0100"),
-        ('5', "0101"),
-        ('6', "0110"),
-        ('7', "0111"),
-        ('8', "1000"),
-        ('9', "1001"),
-        ('A', "1010"),
-        ('B', "1011"),
-        ('C', "1100"),
-        ('D', "1101"),
-        ('E', "1110"),
-        ('F', "1111"),
-    ]);
+/// Day 16: Packet Decoder
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Packet;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Packet::from_hex(input.trim())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(input.version_sum())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        input.value()
+    }
 }
 
+/// The type of an operator packet, literals don't have one as they carry a value instead.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Type {
     Sum,
     Product,
     Minimum,
     Maximum,
-    Literal,
     Greater,
     Less,
     Equal,
 }
 
+/// The type id of a literal, every other id is a [`Type`].
+const LITERAL_ID: u64 = 4;
+
 impl Type {
-    fn from_u64(id: u64) -> Self {
+    fn from_u64(id: u64) -> Option<Self> {
         match id {
-            0 => Self::Sum,
-            1 => Self::Product,
-            2 => Self::Minimum,
-            3 => Self::Maximum,
-            4 => Self::Literal,
-            5 => Self::Greater,
-            6 => Self::Less,
-            7 => Self::Equal,
-            _ => unreachable!(),
+            0 => Some(Self::Sum),
+            1 => Some(Self::Product),
+            2 => Some(Self::Minimum),
+            3 => Some(Self::Maximum),
+            5 => Some(Self::Greater),
+            6 => Some(Self::Less),
+            7 => Some(Self::Equal),
+            _ => None,
+        }
+    }
+
+    /// The name of an operator in expressions, see [`Packet::from_sexpr`].
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Product => "product",
+            Self::Minimum => "min",
+            Self::Maximum => "max",
+            Self::Greater => "gt",
+            Self::Less => "lt",
+            Self::Equal => "eq",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Sum,
+            Self::Product,
+            Self::Minimum,
+            Self::Maximum,
+            Self::Greater,
+            Self::Less,
+            Self::Equal,
+        ]
+        .into_iter()
+        .find(|t| t.name() == name)
+    }
+
+    fn id(self) -> u64 {
+        match self {
+            Self::Sum => 0,
+            Self::Product => 1,
+            Self::Minimum => 2,
+            Self::Maximum => 3,
+            Self::Greater => 5,
+            Self::Less => 6,
+            Self::Equal => 7,
         }
     }
 }
 
+/// How [`Packet::to_hex`] writes the length of the sub-packets of an operator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LengthType {
+    /// Type 0, the number of bits the sub-packets take.
+    Bits,
+    /// Type 1, the number of sub-packets.
+    Count,
+}
+
 #[derive(Debug, Eq, PartialEq)]
-struct Packet {
+pub struct Packet {
     version: u64,
-    type_id: Type,
     payload: PacketPayload,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 enum PacketPayload {
     Literal(u64),
-    SubPacket(Vec<Packet>),
+    SubPacket(Type, Vec<Packet>),
+}
+
+/// How deep packets may nest. Decoding, evaluating and writing packets all recurse into the
+/// sub-packets, so deeper transmissions and expressions are rejected rather than allowed to
+/// overflow the stack.
+pub const MAX_DEPTH: usize = 256;
+
+fn malformed(bits: &BitReader) -> Error {
+    Error::parse(format!("malformed packet at bit {}", bits.position()))
 }
 
-fn from_num(input: &str) -> Result<u64, std::num::ParseIntError> {
-    u64::from_str_radix(input, 2)
+fn read(bits: &mut BitReader, count: u32) -> Result<u64> {
+    bits.read(count).ok_or_else(|| malformed(bits))
 }
 
-fn num_primary(input: &str) -> IResult<&str, u64> {
-    map_res(take(3usize), from_num)(input)
+fn decode_literal(bits: &mut BitReader) -> Result<PacketPayload> {
+    let start = bits.position();
+    let mut value = 0u64;
+    loop {
+        let more = read(bits, 1)? == 1;
+        let group = read(bits, 4)?;
+        //Literals wider than 64 bits are rejected rather than truncated
+        if value.leading_zeros() < 4 {
+            let message = format!("the literal at bit {} does not fit in 64 bits", start);
+            return Err(Error::parse(message));
+        }
+        value = value << 4 | group;
+        if !more {
+            return Ok(Literal(value));
+        }
+    }
+}
+
+fn decode_operator(type_id: Type, bits: &mut BitReader, depth: usize) -> Result<PacketPayload> {
+    let mut packets = Vec::new();
+    if read(bits, 1)? == 0 {
+        let length = read(bits, 15)? as usize;
+        let mut payload = bits.split(length).ok_or_else(|| malformed(bits))?;
+        while payload.remaining() > 0 {
+            packets.push(decode(&mut payload, depth + 1)?);
+        }
+    } else {
+        let count = read(bits, 11)?;
+        for _ in 0..count {
+            packets.push(decode(bits, depth + 1)?);
+        }
+    }
+    Ok(PacketPayload::SubPacket(type_id, packets))
 }
 
-fn parse_header(input: &str) -> IResult<&str, (u64, u64)> {
-    tuple((num_primary, num_primary))(input)
+fn decode(bits: &mut BitReader, depth: usize) -> Result<Packet> {
+    let start = bits.position();
+    if depth > MAX_DEPTH {
+        let message = format!(
+            "the packet at bit {} nests deeper than {}",
+            start, MAX_DEPTH
+        );
+        return Err(Error::parse(message));
+    }
+    let version = read(bits, 3)?;
+    let id = read(bits, 3)?;
+    if id == LITERAL_ID {
+        let payload = decode_literal(bits)?;
+        return Ok(Packet { version, payload });
+    }
+    let type_id = Type::from_u64(id).ok_or_else(|| {
+        Error::parse(format!(
+            "unknown type id {} in the packet at bit {}",
+            id, start
+        ))
+    })?;
+    let payload = decode_operator(type_id, bits, depth)?;
+    Ok(Packet { version, payload })
 }
 
-fn parse_literal(input: &str) -> IResult<&str, PacketPayload> {
-    let (input, (mut chunks, last)) = many_till(
-        preceded(tag("1"), take(4usize)),
-        preceded(tag("0"), take(4usize)),
-    )(input)?;
-    chunks.push(last);
-    let joined = chunks.join("");
-    let payload = u64::from_str_radix(joined.as_str(), 2).unwrap();
-    Ok((input, PacketPayload::Literal(payload)))
+fn encode_literal(value: u64, bits: &mut BitWriter) {
+    let groups = ((64 - value.leading_zeros()) as usize).div_ceil(4).max(1);
+    for group in (0..groups).rev() {
+        bits.write((group > 0) as u64, 1);
+        bits.write(value >> (4 * group) & 0xF, 4);
+    }
 }
 
-fn parse_fixed_length(input: &str) -> IResult<&str, PacketPayload> {
-    let (input, payload_size) = preceded(
-        tag("0"),
-        map_res(take(15usize), |s: &str| u32::from_str_radix(s, 2)),
-    )(input)?;
+fn encode(packet: &Packet, length_type: LengthType, bits: &mut BitWriter) -> Result<()> {
+    if packet.version > 7 {
+        let message = format!("version {} does not fit in 3 bits", packet.version);
+        return Err(Error::invalid(message));
+    }
+    bits.write(packet.version, 3);
+    match &packet.payload {
+        Literal(value) => {
+            bits.write(LITERAL_ID, 3);
+            encode_literal(*value, bits);
+        }
+        PacketPayload::SubPacket(type_id, packets) => {
+            bits.write(type_id.id(), 3);
+            let mut payload = BitWriter::new();
+            for packet in packets {
+                encode(packet, length_type, &mut payload)?;
+            }
+            let fits_bits = payload.len() < 1 << 15;
+            let fits_count = packets.len() < 1 << 11;
+            match (length_type, fits_bits, fits_count) {
+                (LengthType::Bits, true, _) | (LengthType::Count, true, false) => {
+                    bits.write(0, 1);
+                    bits.write(payload.len() as u64, 15);
+                }
+                (_, _, true) => {
+                    bits.write(1, 1);
+                    bits.write(packets.len() as u64, 11);
+                }
+                (_, false, false) => {
+                    let message = format!(
+                        "{} sub-packets of {} bits are too many to encode",
+                        packets.len(),
+                        payload.len()
+                    );
+                    return Err(Error::invalid(message));
+                }
+            }
+            bits.append(&payload);
+        }
+    }
+    Ok(())
+}
 
-    let (rest, payload) = take(payload_size)(input)?;
-    let (_, payload) = many1(packet)(payload)?;
-    Ok((rest, PacketPayload::SubPacket(payload)))
+fn literal(value: u64) -> Packet {
+    Packet {
+        version: 0,
+        payload: Literal(value),
+    }
 }
 
-fn parse_number_packets(input: &str) -> IResult<&str, PacketPayload> {
-    let (input, num_packets) = preceded(
-        tag("1"),
-        map_res(take(11usize), |s: &str| u32::from_str_radix(s, 2)),
-    )(input)?;
+fn operator(type_id: Type, packets: Vec<Packet>) -> Packet {
+    Packet {
+        version: 0,
+        payload: PacketPayload::SubPacket(type_id, packets),
+    }
+}
 
-    let (input, payload) = count(packet, num_packets as usize)(input)?;
+fn ws<'a, T>(
+    parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    delimited(multispace0, parser, multispace0)
+}
 
-    Ok((input, PacketPayload::SubPacket(payload)))
+fn operator_name(input: &str) -> IResult<&str, Type> {
+    map_opt(alpha1, Type::from_name)(input)
 }
 
-fn parse_operator(input: &str) -> IResult<&str, PacketPayload> {
-    alt((parse_fixed_length, parse_number_packets))(input)
+//"(sum 1 (max 3 7) (lt 5 15))"
+fn sexpr(input: &str) -> IResult<&str, Packet> {
+    alt((map(u64, literal), sexpr_list))(input)
 }
 
-fn payload(input: &str, type_id: Type) -> IResult<&str, PacketPayload> {
-    match type_id {
-        Type::Literal => parse_literal(input),
-        _ => parse_operator(input),
-    }
+fn sexpr_list(input: &str) -> IResult<&str, Packet> {
+    let operands = many0(alt((
+        preceded(multispace1, sexpr),
+        preceded(multispace0, sexpr_list),
+    )));
+    map(
+        delimited(
+            terminated(char('('), multispace0),
+            pair(operator_name, operands),
+            preceded(multispace0, char(')')),
+        ),
+        |(type_id, operands)| operator(type_id, operands),
+    )(input)
 }
 
-fn parse_packet_from_hex(input: &str) -> Packet {
-    let bits: String = input
-        .chars()
-        .filter_map(|c| HEX_MAP.get(&c))
-        .map(|r| r.to_string())
-        .collect();
-    packet(&bits).unwrap().1
+//"1 + max(3, 7) * 2 < 20", where comparisons don't chain
+fn comparison(input: &str) -> IResult<&str, Packet> {
+    let symbol = alt((
+        value(Type::Equal, tag("==")),
+        value(Type::Less, char('<')),
+        value(Type::Greater, char('>')),
+    ));
+    let (input, left) = infix_sum(input)?;
+    let (input, right) = opt(pair(ws(symbol), infix_sum))(input)?;
+    let packet = match right {
+        Some((type_id, right)) => operator(type_id, vec![left, right]),
+        None => left,
+    };
+    Ok((input, packet))
 }
 
-fn packet(input: &str) -> IResult<&str, Packet> {
-    let (rest, (version, type_id)) = parse_header(&input)?;
-    let type_id = Type::from_u64(type_id);
-    let (rest, payload) = payload(rest, type_id)?;
-    Ok((
-        rest,
-        Packet {
-            version,
-            type_id,
-            payload,
+//Operands joined by `symbol` become a single packet of `type_id`
+fn chain<'a>(
+    type_id: Type,
+    symbol: char,
+    operand: fn(&'a str) -> IResult<&'a str, Packet>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Packet> {
+    map(
+        separated_list1(ws(char(symbol)), operand),
+        move |mut operands| match operands.len() {
+            1 => operands.remove(0),
+            _ => operator(type_id, operands),
         },
-    ))
+    )
+}
+
+fn infix_sum(input: &str) -> IResult<&str, Packet> {
+    chain(Type::Sum, '+', infix_product)(input)
+}
+
+fn infix_product(input: &str) -> IResult<&str, Packet> {
+    chain(Type::Product, '*', infix_operand)(input)
+}
+
+fn infix_operand(input: &str) -> IResult<&str, Packet> {
+    let call = pair(
+        operator_name,
+        delimited(
+            ws(char('(')),
+            separated_list0(ws(char(',')), comparison),
+            preceded(multispace0, char(')')),
+        ),
+    );
+    alt((
+        map(u64, literal),
+        map(call, |(type_id, operands)| operator(type_id, operands)),
+        delimited(char('('), ws(comparison), char(')')),
+    ))(input)
+}
+
+//Comparisons take exactly two operands, the other operators at least one
+fn check_operand_count(type_id: Type, count: usize) -> Result<()> {
+    let message = match (type_id, count) {
+        (Type::Greater | Type::Less | Type::Equal, 2) => return Ok(()),
+        (Type::Greater | Type::Less | Type::Equal, _) => {
+            format!("needs 2 operands, found {}", count)
+        }
+        (_, 0) => "needs an operand, found 0".to_string(),
+        _ => return Ok(()),
+    };
+    Err(Error::invalid(format!("{} {}", type_id.name(), message)))
+}
+
+fn check_operands(packet: &Packet) -> Result<()> {
+    if let PacketPayload::SubPacket(type_id, packets) = &packet.payload {
+        check_operand_count(*type_id, packets.len())?;
+        packets.iter().try_for_each(check_operands)?;
+    }
+    Ok(())
+}
+
+//Each level of parentheses is a level of packets, and of recursion in the parsers
+fn check_nesting(input: &str) -> Result<()> {
+    let mut depth = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' if depth == MAX_DEPTH => {
+                let message = format!("expressions nest deeper than {}", MAX_DEPTH);
+                return Err(Error::parse(message).at(Location::of(input, &input[i..])));
+            }
+            '(' => depth += 1,
+            ')' => depth = usize::saturating_sub(depth, 1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_packet_from_hex(input: &str) -> Result<Packet> {
+    let bytes = hex_to_bytes(input)?;
+    let mut bits = BitReader::new(&bytes);
+    let packet = decode(&mut bits, 0)?;
+    //Only zeroes may pad the transmission after the packet
+    let end = bits.position();
+    while bits.remaining() > 0 {
+        let chunk = bits.remaining().min(64) as u32;
+        if bits.read(chunk) != Some(0) {
+            let message = format!("unexpected bits after the packet ending at bit {}", end);
+            return Err(Error::parse(message));
+        }
+    }
+    Ok(packet)
 }
 
 impl Packet {
-    fn from_str(input: &str) -> Self {
+    /// Decodes a full hexadecimal transmission, of packets nested at most [`MAX_DEPTH`] deep.
+    pub fn from_hex(input: &str) -> Result<Self> {
         parse_packet_from_hex(input)
     }
 
-    fn version_sum(&self) -> u64 {
+    /// Compiles an s-expression like `(sum 1 (max 3 7) (lt 5 15))` into packets of version 0.
+    ///
+    /// Operators are `sum`, `product`, `min`, `max`, `gt`, `lt` and `eq`, and operands are
+    /// numbers or nested expressions, at most [`MAX_DEPTH`] deep.
+    pub fn from_sexpr(input: &str) -> Result<Self> {
+        check_nesting(input)?;
+        let packet = parse_all(input, ws(sexpr))?;
+        check_operands(&packet)?;
+        Ok(packet)
+    }
+
+    /// Compiles an infix expression like `1 + max(3, 7) * 2 < 20` into packets of version 0.
+    ///
+    /// `*` binds tighter than `+`, which binds tighter than a single `<`, `>` or `==`.
+    /// Parentheses group, and the operators of [`from_sexpr`](Self::from_sexpr) are called
+    /// like functions.
+    pub fn from_infix(input: &str) -> Result<Self> {
+        check_nesting(input)?;
+        let packet = parse_all(input, ws(comparison))?;
+        check_operands(&packet)?;
+        Ok(packet)
+    }
+
+    /// Encodes the packet as a hexadecimal transmission, padded with zeroes to whole bytes.
+    ///
+    /// The sub-packets of operators are measured as `length_type` where it can hold them,
+    /// the other type is used for 2<sup>15</sup> bits or 2<sup>11</sup> sub-packets and up.
+    pub fn to_hex(&self, length_type: LengthType) -> Result<String> {
+        let mut bits = BitWriter::new();
+        encode(self, length_type, &mut bits)?;
+        Ok(bits.to_hex())
+    }
+
+    /// Sum of the versions of this packet and all nested packets.
+    pub fn version_sum(&self) -> u64 {
         self.version + {
             match &self.payload {
                 Literal(_) => 0,
-                PacketPayload::SubPacket(packets) => packets.iter().map(|p| p.version_sum()).sum(),
+                PacketPayload::SubPacket(_, packets) => {
+                    packets.iter().map(|p| p.version_sum()).sum()
+                }
             }
         }
     }
 
-    fn value(&self) -> u64 {
-        match &self.payload {
-            Literal(val) => *val,
-            PacketPayload::SubPacket(subpackets) => {
-                let mut values = subpackets.iter().map(|p| p.value());
-                match &self.type_id {
-                    Type::Sum => values.sum(),
-                    Type::Product => values.product(),
-                    Type::Minimum => values.min().unwrap(),
-                    Type::Maximum => values.max().unwrap(),
-                    Type::Literal => {
-                        unreachable!()
-                    }
-                    Type::Greater => {
-                        if values.next() > values.next() {
-                            1
-                        } else {
-                            0
-                        }
-                    }
-                    Type::Less => {
-                        if values.next() < values.next() {
-                            1
-                        } else {
-                            0
-                        }
-                    }
-                    Type::Equal => {
-                        if values.next() == values.next() {
-                            1
-                        } else {
-                            0
-                        }
-                    }
-                }
+    /// Evaluates the expression this packet encodes.
+    ///
+    /// Sums and products that don't fit in 64 bits are an error rather than wrapping around, as
+    /// are comparisons without exactly two operands and other operators without any.
+    pub fn value(&self) -> Result<u64> {
+        let (type_id, subpackets) = match &self.payload {
+            Literal(val) => return Ok(*val),
+            PacketPayload::SubPacket(type_id, subpackets) => (type_id, subpackets),
+        };
+        check_operand_count(*type_id, subpackets.len())?;
+        let values = subpackets
+            .iter()
+            .map(|p| p.value())
+            .collect::<Result<Vec<_>>>()?;
+        let overflow = || Error::invalid(format!("{} overflows 64 bits", type_id.name()));
+        Ok(match type_id {
+            Type::Sum => values
+                .into_iter()
+                .try_fold(0u64, u64::checked_add)
+                .ok_or_else(overflow)?,
+            Type::Product => values
+                .into_iter()
+                .try_fold(1u64, u64::checked_mul)
+                .ok_or_else(overflow)?,
+            Type::Minimum => values.into_iter().fold(u64::MAX, u64::min),
+            Type::Maximum => values.into_iter().fold(0, u64::max),
+            Type::Greater => (values[0] > values[1]) as u64,
+            Type::Less => (values[0] < values[1]) as u64,
+            Type::Equal => (values[0] == values[1]) as u64,
+        })
+    }
+}
+
+//How tightly infix operators bind, operands binding looser than their operator need parentheses
+const COMPARISON: u8 = 0;
+const SUM: u8 = 1;
+const PRODUCT: u8 = 2;
+
+fn write_outline(packet: &Packet, depth: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:indent$}v{} ", "", packet.version, indent = depth * 2)?;
+    match &packet.payload {
+        Literal(value) => write!(f, "literal {}", value),
+        PacketPayload::SubPacket(type_id, packets) => {
+            write!(f, "{}", type_id.name())?;
+            for packet in packets {
+                writeln!(f)?;
+                write_outline(packet, depth + 1, f)?;
             }
+            Ok(())
+        }
+    }
+}
+
+fn write_operands(
+    packets: &[Packet],
+    separator: &str,
+    binding: u8,
+    f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+    for (i, packet) in packets.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{}", separator)?;
+        }
+        write_expression(packet, binding, f)?;
+    }
+    Ok(())
+}
+
+//Writes what `Packet::from_infix` reads back into the same tree, leaving out the versions
+fn write_expression(packet: &Packet, binding: u8, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let (type_id, packets) = match &packet.payload {
+        Literal(value) => return write!(f, "{}", value),
+        PacketPayload::SubPacket(type_id, packets) => (*type_id, packets),
+    };
+    let (symbol, own) = match type_id {
+        Type::Sum if packets.len() > 1 => (" + ", SUM),
+        Type::Product if packets.len() > 1 => (" * ", PRODUCT),
+        Type::Greater if packets.len() == 2 => (" > ", COMPARISON),
+        Type::Less if packets.len() == 2 => (" < ", COMPARISON),
+        Type::Equal if packets.len() == 2 => (" == ", COMPARISON),
+        type_id => {
+            write!(f, "{}(", type_id.name())?;
+            write_operands(packets, ", ", COMPARISON, f)?;
+            return write!(f, ")");
+        }
+    };
+    if own < binding {
+        write!(f, "(")?;
+    }
+    //Nested sums and products are kept apart, and comparisons don't chain
+    write_operands(packets, symbol, own + 1, f)?;
+    if own < binding {
+        write!(f, ")")?;
+    }
+    Ok(())
+}
+
+/// `{}` writes the packet tree as an outline with the version and type of every packet, and
+/// `{:#}` the expression it encodes with its value, like `max(7, 8, 9) = 9`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() {
+            return write_outline(self, 0, f);
+        }
+        write_expression(self, COMPARISON, f)?;
+        match self.value() {
+            Ok(value) => write!(f, " = {}", value),
+            Err(e) => write!(f, " ({})", e),
         }
     }
 }
@@ -220,19 +579,35 @@ impl Packet {
 /// If first body bit is 1, next 11 bits represent number of sub-packets immediately contained by this packet
 #[cfg(test)]
 mod test {
+    use crate::answers::recorded;
     use crate::day16::PacketPayload::SubPacket;
+    use crate::input::real_input;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     use super::*;
 
+    //Packs a string of '0' and '1' into hexadecimal, padded with zeroes
+    fn hex(bits: &str) -> String {
+        let padded = format!("{}{}", bits, "0".repeat((4 - bits.len() % 4) % 4));
+        padded
+            .as_bytes()
+            .chunks(4)
+            .map(|nibble| {
+                let nibble = std::str::from_utf8(nibble).unwrap();
+                format!("{:X}", u8::from_str_radix(nibble, 2).unwrap())
+            })
+            .collect()
+    }
+
     #[test]
     fn part_one_small_1() {
         assert_eq!(
             Packet {
                 version: 6,
-                type_id: Type::from_u64(4),
                 payload: Literal(2021),
             },
-            Packet::from_str("D2FE28")
+            Packet::from_hex("D2FE28").unwrap()
         );
     }
 
@@ -241,21 +616,21 @@ mod test {
         assert_eq!(
             Packet {
                 version: 1,
-                type_id: Type::from_u64(6),
-                payload: SubPacket(vec![
-                    Packet {
-                        version: 6,
-                        type_id: Type::from_u64(4),
-                        payload: Literal(10),
-                    },
-                    Packet {
-                        version: 2,
-                        type_id: Type::from_u64(4),
-                        payload: Literal(20),
-                    },
-                ]),
+                payload: SubPacket(
+                    Type::from_u64(6).unwrap(),
+                    vec![
+                        Packet {
+                            version: 6,
+                            payload: Literal(10),
+                        },
+                        Packet {
+                            version: 2,
+                            payload: Literal(20),
+                        },
+                    ]
+                ),
             },
-            Packet::from_str("38006F45291200")
+            Packet::from_hex("38006F45291200").unwrap()
         );
     }
 
@@ -264,49 +639,53 @@ mod test {
         assert_eq!(
             Packet {
                 version: 7,
-                type_id: Type::from_u64(3),
-                payload: SubPacket(vec![
-                    Packet {
-                        version: 2,
-                        type_id: Type::from_u64(4),
-                        payload: Literal(1),
-                    },
-                    Packet {
-                        version: 4,
-                        type_id: Type::from_u64(4),
-                        payload: Literal(2),
-                    },
-                    Packet {
-                        version: 1,
-                        type_id: Type::from_u64(4),
-                        payload: Literal(3),
-                    },
-                ]),
+                payload: SubPacket(
+                    Type::from_u64(3).unwrap(),
+                    vec![
+                        Packet {
+                            version: 2,
+                            payload: Literal(1),
+                        },
+                        Packet {
+                            version: 4,
+                            payload: Literal(2),
+                        },
+                        Packet {
+                            version: 1,
+                            payload: Literal(3),
+                        },
+                    ]
+                ),
             },
-            Packet::from_str("EE00D40C823060")
+            Packet::from_hex("EE00D40C823060").unwrap()
         );
     }
 
     #[test]
     fn part_one_small_4() {
-        let packet = Packet::from_str("8A004A801A8002F478");
+        let packet = Packet::from_hex("8A004A801A8002F478").unwrap();
         assert_eq!(
             Packet {
                 version: 4,
-                type_id: Type::from_u64(2),
-                payload: PacketPayload::SubPacket(vec![Packet {
-                    version: 1,
-                    type_id: Type::from_u64(2),
-                    payload: PacketPayload::SubPacket(vec![Packet {
-                        version: 5,
-                        type_id: Type::from_u64(2),
-                        payload: PacketPayload::SubPacket(vec![Packet {
-                            version: 6,
-                            type_id: Type::from_u64(4),
-                            payload: Literal(15),
-                        }]),
-                    }]),
-                }]),
+                payload: PacketPayload::SubPacket(
+                    Type::from_u64(2).unwrap(),
+                    vec![Packet {
+                        version: 1,
+                        payload: PacketPayload::SubPacket(
+                            Type::from_u64(2).unwrap(),
+                            vec![Packet {
+                                version: 5,
+                                payload: PacketPayload::SubPacket(
+                                    Type::from_u64(2).unwrap(),
+                                    vec![Packet {
+                                        version: 6,
+                                        payload: Literal(15),
+                                    }]
+                                ),
+                            }]
+                        ),
+                    }]
+                ),
             },
             packet
         );
@@ -315,7 +694,7 @@ mod test {
 
     #[test]
     fn part_one_small_5() {
-        let packet = Packet::from_str("620080001611562C8802118E34");
+        let packet = Packet::from_hex("620080001611562C8802118E34").unwrap();
 
         assert_eq!(12, packet.version_sum());
     }
@@ -324,7 +703,9 @@ mod test {
     fn part_one_small_6() {
         assert_eq!(
             23,
-            Packet::from_str("C0015000016115A2E0802F182340").version_sum()
+            Packet::from_hex("C0015000016115A2E0802F182340")
+                .unwrap()
+                .version_sum()
         );
     }
 
@@ -332,17 +713,423 @@ mod test {
     fn part_one_small_7() {
         assert_eq!(
             31,
-            Packet::from_str("A0016C880162017C3686B18A3D4780").version_sum()
+            Packet::from_hex("A0016C880162017C3686B18A3D4780")
+                .unwrap()
+                .version_sum()
         );
     }
 
     #[test]
     fn part_one() {
-        assert_eq!(984, Packet::from_str("E20D41802B2984BD00540010F82D09E35880350D61A41D3004E5611E585F40159ED7AD7C90CF6BD6BE49C802DEB00525272CC1927752698693DA7C70029C0081002140096028C5400F6023C9C00D601ED88070070030005C2201448400E400F40400C400A50801E20004C1000809D14700B67676EE661137ADC64FF2BBAD745B3F2D69026335E92A0053533D78932A9DFE23AC7858C028920A973785338832CFA200F47C81D2BBBC7F9A9E1802FE00ACBA44F4D1E775DDC19C8054D93B7E72DBE7006AA200C41A8510980010D8731720CB80132918319804738AB3A8D3E773C4A4015A498E680292B1852E753E2B29D97F0DE6008CB3D4D031802D2853400D24DEAE0137AB8210051D24EB600844B95C56781B3004F002B99D8F635379EDE273AF26972D4A5610BA51004C12D1E25D802F32313239377B37100105343327E8031802B801AA00021D07231C2F10076184668693AC6600BCD83E8025231D752E5ADE311008A4EA092754596C6789727F069F99A4645008247D2579388DCF53558AE4B76B257200AAB80107947E94789FE76E36402868803F0D62743F00043A1646288800084C3F8971308032996A2BD8023292DF8BE467BB3790047F2572EF004A699E6164C013A007C62848DE91CC6DB459B6B40087E530AB31EE633BD23180393CBF36333038E011CBCE73C6FB098F4956112C98864EA1C2801D2D0F319802D60088002190620E479100622E4358952D84510074C0188CF0923410021F1CE1146E3006E3FC578EE600A4B6C4B002449C97E92449C97E92459796EB4FF874400A9A16100A26CEA6D0E5E5EC8841C9B8FE37109C99818023A00A4FD8BA531586BB8B1DC9AE080293B6972B7FA444285CC00AE492BC910C1697B5BDD8425409700562F471201186C0120004322B42489A200D4138A71AA796D00374978FE07B2314E99BFB6E909678A0").version_sum());
+        assert_eq!(
+            recorded(16, 1),
+            Day16::part_one(&Day16::parse(&real_input(16)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 
     #[test]
     fn part_two() {
-        assert_eq!(984, Packet::from_str("E20D41802B2984BD00540010F82D09E35880350D61A41D3004E5611E585F40159ED7AD7C90CF6BD6BE49C802DEB00525272CC1927752698693DA7C70029C0081002140096028C5400F6023C9C00D601ED88070070030005C2201448400E400F40400C400A50801E20004C1000809D14700B67676EE661137ADC64FF2BBAD745B3F2D69026335E92A0053533D78932A9DFE23AC7858C028920A973785338832CFA200F47C81D2BBBC7F9A9E1802FE00ACBA44F4D1E775DDC19C8054D93B7E72DBE7006AA200C41A8510980010D8731720CB80132918319804738AB3A8D3E773C4A4015A498E680292B1852E753E2B29D97F0DE6008CB3D4D031802D2853400D24DEAE0137AB8210051D24EB600844B95C56781B3004F002B99D8F635379EDE273AF26972D4A5610BA51004C12D1E25D802F32313239377B37100105343327E8031802B801AA00021D07231C2F10076184668693AC6600BCD83E8025231D752E5ADE311008A4EA092754596C6789727F069F99A4645008247D2579388DCF53558AE4B76B257200AAB80107947E94789FE76E36402868803F0D62743F00043A1646288800084C3F8971308032996A2BD8023292DF8BE467BB3790047F2572EF004A699E6164C013A007C62848DE91CC6DB459B6B40087E530AB31EE633BD23180393CBF36333038E011CBCE73C6FB098F4956112C98864EA1C2801D2D0F319802D60088002190620E479100622E4358952D84510074C0188CF0923410021F1CE1146E3006E3FC578EE600A4B6C4B002449C97E92449C97E92459796EB4FF874400A9A16100A26CEA6D0E5E5EC8841C9B8FE37109C99818023A00A4FD8BA531586BB8B1DC9AE080293B6972B7FA444285CC00AE492BC910C1697B5BDD8425409700562F471201186C0120004322B42489A200D4138A71AA796D00374978FE07B2314E99BFB6E909678A0").value());
+        assert_eq!(
+            recorded(16, 2),
+            Day16::part_two(&Day16::parse(&real_input(16)).unwrap())
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(
+            "parse error at line 1, column 3: expected a hexadecimal digit, found 'x'",
+            Packet::from_hex("D2xE28").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "parse error: malformed packet at bit 7",
+            Packet::from_hex("D2").unwrap_err().to_string()
+        );
+        //The sub-packets of 38006F45291200 take 27 bits, not 26
+        assert_eq!(
+            "parse error: malformed packet at bit 45",
+            Packet::from_hex("38006945291200").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "parse error: unexpected bits after the packet ending at bit 21",
+            Packet::from_hex("D2FE29").unwrap_err().to_string()
+        );
+        let literal = |groups| format!("100100{}01111", "11111".repeat(groups));
+        assert_eq!(
+            "parse error: the literal at bit 6 does not fit in 64 bits",
+            Packet::from_hex(&hex(&literal(16)))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            Ok(Literal(u64::MAX)),
+            Packet::from_hex(&hex(&literal(15))).map(|p| p.payload)
+        );
+    }
+
+    #[test]
+    fn operands() {
+        //Any number of operands decodes, only evaluating the packets checks them
+        let decoded = |packet: Packet| {
+            let hex = packet.to_hex(LengthType::Count).unwrap();
+            Packet::from_hex(&hex).unwrap()
+        };
+        let error = |packet: Packet| decoded(packet).value().unwrap_err().to_string();
+        let gt = decoded(operator(
+            Type::Greater,
+            vec![literal(1), literal(2), literal(3)],
+        ));
+        assert_eq!(0, gt.version_sum());
+        assert_eq!(
+            "v0 gt\n  v0 literal 1\n  v0 literal 2\n  v0 literal 3",
+            gt.to_string()
+        );
+        assert_eq!(
+            "gt(1, 2, 3) (invalid input: gt needs 2 operands, found 3)",
+            format!("{:#}", gt)
+        );
+        let lt = operator(Type::Less, vec![literal(2)]);
+        assert_eq!(
+            "invalid input: lt needs 2 operands, found 1",
+            error(operator(Type::Sum, vec![literal(1), lt]))
+        );
+        assert_eq!(
+            "invalid input: product needs an operand, found 0",
+            error(operator(Type::Product, vec![]))
+        );
+        assert_eq!(None, Type::from_u64(8));
+    }
+
+    #[test]
+    fn checked_value() {
+        let value = |infix| Packet::from_infix(infix).and_then(|p| p.value());
+        assert_eq!(Ok(u64::MAX), value("18446744073709551614 + 1"));
+        assert_eq!(
+            "invalid input: sum overflows 64 bits",
+            value("18446744073709551615 + 1").unwrap_err().to_string()
+        );
+        assert_eq!(Ok(1 << 63), value("4294967296 * 2147483648"));
+        assert_eq!(
+            "invalid input: product overflows 64 bits",
+            value("4294967296 * 4294967296").unwrap_err().to_string()
+        );
+        //Overflow anywhere in the tree, even under a comparison
+        assert_eq!(
+            "invalid input: product overflows 64 bits",
+            value("min(1, 4294967296 * 4294967296 > 0)")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(Ok(u64::MAX), value("max(0, 18446744073709551615)"));
+        assert_eq!(Ok(0), value("min(18446744073709551615, 0)"));
+    }
+
+    const EXAMPLES: [&str; 15] = [
+        "D2FE28",
+        "38006F45291200",
+        "EE00D40C823060",
+        "8A004A801A8002F478",
+        "620080001611562C8802118E34",
+        "C0015000016115A2E0802F182340",
+        "A0016C880162017C3686B18A3D4780",
+        "C200B40A82",
+        "04005AC33890",
+        "880086C3E88112",
+        "CE00C43D881120",
+        "D8005AC2A8F0",
+        "F600BC2D8F",
+        "9C005AC2F8F0",
+        "9C0141080250320F1802104A08",
+    ];
+
+    fn round_trip(packet: &Packet, length_type: LengthType) -> Packet {
+        Packet::from_hex(&packet.to_hex(length_type).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn encode() {
+        let encoded = |hex| Packet::from_hex(hex).unwrap().to_hex(LengthType::Count);
+        assert_eq!(Ok("D2FE28".to_string()), encoded("D2FE28"));
+        assert_eq!(Ok("EE00D40C823060".to_string()), encoded("EE00D40C823060"));
+        assert_eq!(
+            Ok("38006F45291200".to_string()),
+            Packet::from_hex("38006F45291200")
+                .unwrap()
+                .to_hex(LengthType::Bits)
+        );
+        let literal = |value| Packet {
+            version: 0,
+            payload: Literal(value),
+        };
+        assert_eq!(Ok("1000".to_string()), literal(0).to_hex(LengthType::Bits));
+        assert_eq!(
+            Ok("1261".to_string()),
+            literal(0x31).to_hex(LengthType::Bits)
+        );
+        let packet = Packet {
+            version: 8,
+            ..literal(1)
+        };
+        assert_eq!(
+            "invalid input: version 8 does not fit in 3 bits",
+            packet.to_hex(LengthType::Bits).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn round_trip_examples() {
+        for hex in EXAMPLES {
+            let packet = Packet::from_hex(hex).unwrap();
+            for length_type in [LengthType::Bits, LengthType::Count] {
+                assert_eq!(packet, round_trip(&packet, length_type), "{}", hex);
+            }
+        }
+        let packet = Day16::parse(&real_input(16)).unwrap();
+        assert_eq!(packet, round_trip(&packet, LengthType::Bits));
+    }
+
+    fn random_packet(rng: &mut StdRng, depth: u32) -> Packet {
+        let version = rng.gen_range(0..8);
+        if depth == 0 || rng.gen_bool(0.3) {
+            let bits = rng.gen_range(0..=64);
+            let value = rng.gen::<u64>().checked_shr(64 - bits).unwrap_or(0);
+            return Packet {
+                version,
+                payload: Literal(value),
+            };
+        }
+        let type_id = Type::from_u64([0, 1, 2, 3, 5, 6, 7][rng.gen_range(0..7)]).unwrap();
+        let count = match type_id {
+            Type::Greater | Type::Less | Type::Equal => 2,
+            _ => rng.gen_range(1..5),
+        };
+        let packets = (0..count).map(|_| random_packet(rng, depth - 1)).collect();
+        Packet {
+            version,
+            payload: PacketPayload::SubPacket(type_id, packets),
+        }
+    }
+
+    #[test]
+    fn round_trip_random() {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..500 {
+            let packet = random_packet(&mut rng, 5);
+            for length_type in [LengthType::Bits, LengthType::Count] {
+                assert_eq!(packet, round_trip(&packet, length_type));
+            }
+        }
+    }
+
+    #[test]
+    fn length_limits() {
+        let operator = |values: Vec<u64>| Packet {
+            version: 1,
+            payload: PacketPayload::SubPacket(
+                Type::Sum,
+                values
+                    .into_iter()
+                    .map(|value| Packet {
+                        version: 2,
+                        payload: Literal(value),
+                    })
+                    .collect(),
+            ),
+        };
+        //Too many sub-packets to count, but few enough bits to measure
+        let packet = operator(vec![1; 2048]);
+        assert!(packet
+            .to_hex(LengthType::Count)
+            .unwrap()
+            .starts_with("2160"));
+        assert_eq!(packet, round_trip(&packet, LengthType::Count));
+        //Too many bits to measure, so counted
+        let packet = operator(vec![u64::MAX; 400]);
+        assert!(packet.to_hex(LengthType::Bits).unwrap().starts_with("2264"));
+        assert_eq!(packet, round_trip(&packet, LengthType::Bits));
+        assert_eq!(
+            "invalid input: 2048 sub-packets of 176128 bits are too many to encode",
+            operator(vec![u64::MAX; 2048])
+                .to_hex(LengthType::Bits)
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn compile() {
+        let packet = Packet::from_sexpr("(sum 1 (max 3 7) (lt 5 15))").unwrap();
+        assert_eq!(
+            operator(
+                Type::Sum,
+                vec![
+                    literal(1),
+                    operator(Type::Maximum, vec![literal(3), literal(7)]),
+                    operator(Type::Less, vec![literal(5), literal(15)]),
+                ]
+            ),
+            packet
+        );
+        assert_eq!(Ok(9), packet.value());
+        assert_eq!(
+            packet,
+            Packet::from_infix("1 + max(3,7) + (5 < 15)").unwrap()
+        );
+        assert_eq!(
+            packet,
+            Packet::from_sexpr("\n( sum 1(max 3\n 7)(lt 5 15 ) )\n").unwrap()
+        );
+
+        let value = |infix| Packet::from_infix(infix).and_then(|p| p.value());
+        assert_eq!(Ok(8), value("1 + max(3,7)"));
+        assert_eq!(Ok(10), value("2 * 3 + 4"));
+        assert_eq!(Ok(14), value("2*(3+4)"));
+        assert_eq!(Ok(1), value("min(5, 2 * 3) == 5"));
+        assert_eq!(Ok(0), value("product(2, 3) > sum(4, 5)"));
+        assert_eq!(Ok(42), value("42"));
+
+        //Hand written regression inputs survive the trip through hex
+        let packet = Packet::from_infix("eq(sum(1, 3), product(2, 2)) * 1000").unwrap();
+        let hex = packet.to_hex(LengthType::Count).unwrap();
+        assert_eq!(Ok(1000), Packet::from_hex(&hex).unwrap().value());
+    }
+
+    #[test]
+    fn compile_errors() {
+        let error = |result: Result<Packet>| result.unwrap_err().to_string();
+        assert_eq!(
+            "invalid input: gt needs 2 operands, found 1",
+            error(Packet::from_sexpr("(sum 1 (gt 3))"))
+        );
+        assert_eq!(
+            "invalid input: max needs an operand, found 0",
+            error(Packet::from_infix("1 + max()"))
+        );
+        assert_eq!(
+            "parse error at line 1, column 3: unexpected \"- 2\"",
+            error(Packet::from_infix("1 - 2"))
+        );
+        assert_eq!(
+            "parse error at line 1, column 7: unexpected \"< 3\"",
+            error(Packet::from_infix("1 < 2 < 3"))
+        );
+        assert!(Packet::from_sexpr("(avg 1 2)").is_err());
+        assert!(Packet::from_sexpr("(sum1 2)").is_err());
+        assert!(Packet::from_infix("sum 1 2").is_err());
+    }
+
+    #[test]
+    fn display() {
+        let packet = Packet::from_hex("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(
+            "v4 eq\n  v2 sum\n    v2 literal 1\n    v4 literal 3\n  v6 product\n    v0 literal 2\n    v2 literal 2",
+            packet.to_string()
+        );
+        assert_eq!("1 + 3 == 2 * 2 = 1", format!("{:#}", packet));
+        let packet = Packet::from_hex("CE00C43D881120").unwrap();
+        assert_eq!("max(7, 8, 9) = 9", format!("{:#}", packet));
+        assert_eq!(
+            "v6 literal 2021",
+            Packet::from_hex("D2FE28").unwrap().to_string()
+        );
+
+        let expression = |sexpr| format!("{:#}", Packet::from_sexpr(sexpr).unwrap());
+        assert_eq!(
+            "(2 + 3) * 4 + (5 < 6) = 21",
+            expression("(sum (product (sum 2 3) 4) (lt 5 6))")
+        );
+        assert_eq!(
+            "1 + (2 + 3) + sum(4) = 10",
+            expression("(sum 1 (sum 2 3) (sum 4))")
+        );
+        assert_eq!(
+            "min(1 + 2, 3 > 4) = 0",
+            expression("(min (sum 1 2) (gt 3 4))")
+        );
+        assert_eq!("(1 == 1) < 2 = 1", expression("(lt (eq 1 1) 2)"));
+        //Packets without a value show why
+        let overflow = Packet::from_infix("18446744073709551615 + 1").unwrap();
+        assert_eq!(
+            "18446744073709551615 + 1 (invalid input: sum overflows 64 bits)",
+            format!("{:#}", overflow)
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let input = real_input(16);
+        for hex in EXAMPLES.iter().copied().chain([input.trim()]) {
+            let packet = Packet::from_hex(hex).unwrap();
+            let shown = format!("{:#}", packet);
+            let (expression, value) = shown.rsplit_once(" = ").unwrap();
+            let compiled = Packet::from_infix(expression).unwrap();
+            assert_eq!(format!("{:#}", compiled), shown);
+            assert_eq!(packet.value().unwrap().to_string(), value);
+        }
+    }
+
+    #[test]
+    fn nesting() {
+        //Sums of a single, counted operand around a literal 1
+        let nested = |depth| {
+            hex(&format!(
+                "{}00010000001",
+                "000000100000000001".repeat(depth)
+            ))
+        };
+        let packet = Packet::from_hex(&nested(MAX_DEPTH)).unwrap();
+        assert_eq!(Ok(1), packet.value());
+        assert_eq!(0, packet.version_sum());
+        assert_eq!(MAX_DEPTH + 1, packet.to_string().lines().count());
+        let shown = format!("{:#}", packet);
+        let (expression, _) = shown.rsplit_once(" = ").unwrap();
+        assert_eq!(packet, Packet::from_infix(expression).unwrap());
+        assert_eq!(packet, round_trip(&packet, LengthType::Count));
+
+        let error = format!(
+            "parse error: the packet at bit {} nests deeper than {}",
+            (MAX_DEPTH + 1) * 18,
+            MAX_DEPTH
+        );
+        assert_eq!(
+            error,
+            Packet::from_hex(&nested(MAX_DEPTH + 1))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            error,
+            Packet::from_hex(&nested(200_000)).unwrap_err().to_string()
+        );
+        let sexpr = format!("{}1{}", "(sum ".repeat(257), ")".repeat(257));
+        assert_eq!(
+            "parse error at line 1, column 1281: expressions nest deeper than 256",
+            Packet::from_sexpr(&sexpr).unwrap_err().to_string()
+        );
+        //Three packets deep for every parenthesis, a comparison, a sum and a product
+        let infix = |depth| format!("{}1{}", "(1 + 1 * ".repeat(depth), " > 0)".repeat(depth));
+        assert_eq!(
+            Ok(1),
+            Packet::from_infix(&infix(MAX_DEPTH)).and_then(|p| p.value())
+        );
+        assert!(Packet::from_infix(&infix(MAX_DEPTH + 1)).is_err());
+        let sexpr = format!("{}1{}", "(max ".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+        assert_eq!(Ok(1), Packet::from_sexpr(&sexpr).and_then(|p| p.value()));
+    }
+
+    #[test]
+    fn long_transmission() {
+        //A sum of 2000 literals, most of the way to the 15 bit length limit
+        //Version 4, type 4 and the single group 0 1000
+        let literal = "10010001000";
+        let payload = literal.repeat(2000);
+        let bits = format!("0000000{:015b}{}", payload.len(), payload);
+        let packet = Packet::from_hex(&hex(&bits)).unwrap();
+        assert_eq!(8000, packet.version_sum());
+        assert_eq!(Ok(16000), packet.value());
     }
 }
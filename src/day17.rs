@@ -1,7 +1,51 @@
 use std::mem::swap;
 
+use nom::bytes::complete::tag;
+use nom::sequence::{preceded, separated_pair};
+use nom::IResult;
 use roots::{find_roots_quadratic, Roots};
 
+use crate::error::{parse_all, Error, Result};
+use crate::parsers::{key_value, range};
+use crate::solution::Solution;
+
+type Target = ((i32, i32), (i32, i32));
+
+/// Day 17: Trick Shot
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Target;
+    type PartOne = u64;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let target @ ((x_start, x_end), (y_start, y_end)) = parse_all(input.trim(), parse_target)?;
+        if x_start > x_end || y_start > y_end {
+            return Err(Error::invalid("target ranges must run from low to high"));
+        }
+        Ok(target)
+    }
+
+    fn part_one((_, (y_start, y_end)): &Self::Input) -> Result<Self::PartOne> {
+        let y = find_opt_y(*y_start, *y_end)
+            .ok_or(Error::no_solution("no launch velocity hits the target"))?;
+        Ok(peak(y as f64) as u64)
+    }
+
+    fn part_two((x, y): &Self::Input) -> Result<Self::PartTwo> {
+        Ok(all_solutions(*x, *y).len())
+    }
+}
+
+//"target area: x=20..30, y=-10..-5"
+fn parse_target(input: &str) -> IResult<&str, Target> {
+    preceded(
+        tag("target area: "),
+        separated_pair(key_value("x", range), tag(", "), key_value("y", range)),
+    )(input)
+}
+
 fn simulate(v_0: f64, t: f64) -> f64 {
     -0.5f64 * (t * t) + ((v_0 + 0.5f64) * t)
 }
@@ -34,15 +78,13 @@ fn step_number(t: f64, is_first: bool) -> u64 {
 fn same_step(t0: f64, t1: f64) -> bool {
     let t0 = step_number(t0, true);
     let t1 = step_number(t1, false);
-    return t0 == t1;
+    t0 == t1
 }
 
 fn y_on_target(y0: f64, y_start: f64, y_end: f64) -> Option<(u64, u64)> {
     let t1 = solve_for_t(y0, y_start);
     let t0 = solve_for_t(y0, y_end);
-    if t0.is_none() {
-        return None;
-    }
+    t0?;
 
     let mut t0 = t0.unwrap().ceil();
     if let Some(t1) = t1 {
@@ -60,7 +102,7 @@ fn y_on_target(y0: f64, y_start: f64, y_end: f64) -> Option<(u64, u64)> {
     }
 }
 
-fn find_opt_y(y_start: i32, y_end: i32) -> u64 {
+fn find_opt_y(y_start: i32, y_end: i32) -> Option<u64> {
     let y_start = y_start as f64;
     let y_end = y_end as f64;
     (0..10000)
@@ -68,7 +110,6 @@ fn find_opt_y(y_start: i32, y_end: i32) -> u64 {
         .filter(|y0| y_on_target(*y0, y_start, y_end).is_some())
         .map(|y| y as u64)
         .max()
-        .unwrap()
 }
 
 fn peak(v_0: f64) -> f64 {
@@ -104,7 +145,7 @@ fn simulate_x(v_0: i32, (x_s, x_e): (i32, i32)) -> Option<(i32, (u64, u64))> {
             };
         }
     }
-    return None;
+    None
 }
 
 fn all_solutions((x_s, x_e): (i32, i32), (y_s, y_e): (i32, i32)) -> Vec<(i32, i32)> {
@@ -113,7 +154,7 @@ fn all_solutions((x_s, x_e): (i32, i32), (y_s, y_e): (i32, i32)) -> Vec<(i32, i3
 
     all_ys
         .iter()
-        .map(|(y, (s_y, e_y))| {
+        .flat_map(|(y, (s_y, e_y))| {
             all_xs
                 .iter()
                 .filter_map(|(x, (s_x, e_x))| {
@@ -127,7 +168,6 @@ fn all_solutions((x_s, x_e): (i32, i32), (y_s, y_e): (i32, i32)) -> Vec<(i32, i3
                 })
                 .collect::<Vec<(i32, i32)>>()
         })
-        .flatten()
         .collect::<Vec<_>>()
 }
 
@@ -155,8 +195,28 @@ fn all_solutions_x(x_s: i32, x_e: i32) -> Vec<(i32, (u64, u64))> {
 
 #[cfg(test)]
 mod test {
+    use crate::answers::recorded;
+    use crate::error::parse_lines;
+    use crate::examples::example;
+    use crate::input::real_input;
+    use crate::parsers::coordinates;
+
     use super::*;
 
+    #[test]
+    fn parse() {
+        assert_eq!(
+            Ok(((20, 30), (-10, -5))),
+            Day17::parse("target area: x=20..30, y=-10..-5\n")
+        );
+        assert_eq!(
+            "parse error at line 1, column 31: unexpected \"z\" (Digit)",
+            Day17::parse("target area: x=20..30, y=-10..z")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
     #[test]
     fn func() {
         let v_0 = 3f64;
@@ -180,16 +240,20 @@ mod test {
     //
     #[test]
     fn part_one_small() {
-        let opt_y = find_opt_y(-10, -5);
+        let opt_y = find_opt_y(-10, -5).unwrap();
         assert_eq!(9, opt_y);
         assert_eq!(45, peak(opt_y as f64) as u64);
     }
 
     #[test]
     fn part_one() {
-        let opt_y = find_opt_y(-126, -69);
-        assert_eq!(125, opt_y);
-        assert_eq!(7875, peak(opt_y as f64) as u64);
+        assert_eq!(Some(125), find_opt_y(-126, -69));
+        assert_eq!(
+            recorded(17, 1),
+            Day17::part_one(&Day17::parse(&real_input(17)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 
     #[test]
@@ -220,10 +284,10 @@ mod test {
 
     #[test]
     fn pt_2_same_step() {
-        assert_eq!(false, same_step(1.0, 1.884));
-        assert_eq!(false, same_step(8.6, 9.17));
-        assert_eq!(false, same_step(10.0, 10.844288770224761));
-        assert_eq!(true, same_step(23.426860441876563, 23.844288770224761));
+        assert!(!same_step(1.0, 1.884));
+        assert!(!same_step(8.6, 9.17));
+        assert!(!same_step(10.0, 10.844288770224761));
+        assert!(same_step(23.426860441876563, 23.844_288_770_224_76));
     }
 
     #[test]
@@ -242,124 +306,19 @@ mod test {
     }
 
     fn test_output() -> Vec<(i32, i32)> {
-        vec![
-            (23, -10),
-            (25, -9),
-            (27, -5),
-            (29, -6),
-            (22, -6),
-            (21, -7),
-            (9, 0),
-            (27, -7),
-            (24, -5),
-            (25, -7),
-            (26, -6),
-            (25, -5),
-            (6, 8),
-            (11, -2),
-            (20, -5),
-            (29, -10),
-            (6, 3),
-            (28, -7),
-            (8, 0),
-            (30, -6),
-            (29, -8),
-            (20, -10),
-            (6, 7),
-            (6, 4),
-            (6, 1),
-            (14, -4),
-            (21, -6),
-            (26, -10),
-            (7, -1),
-            (7, 7),
-            (8, -1),
-            (21, -9),
-            (6, 2),
-            (20, -7),
-            (30, -10),
-            (14, -3),
-            (20, -8),
-            (13, -2),
-            (7, 3),
-            (28, -8),
-            (29, -9),
-            (15, -3),
-            (22, -5),
-            (26, -8),
-            (25, -8),
-            (25, -6),
-            (15, -4),
-            (9, -2),
-            (15, -2),
-            (12, -2),
-            (28, -9),
-            (12, -3),
-            (24, -6),
-            (23, -7),
-            (25, -10),
-            (7, 8),
-            (11, -3),
-            (26, -7),
-            (7, 1),
-            (23, -9),
-            (6, 0),
-            (22, -10),
-            (27, -6),
-            (8, 1),
-            (22, -8),
-            (13, -4),
-            (7, 6),
-            (28, -6),
-            (11, -4),
-            (12, -4),
-            (26, -9),
-            (7, 4),
-            (24, -10),
-            (23, -8),
-            (30, -8),
-            (7, 0),
-            (9, -1),
-            (10, -1),
-            (26, -5),
-            (22, -9),
-            (6, 5),
-            (7, 5),
-            (23, -6),
-            (28, -10),
-            (10, -2),
-            (11, -1),
-            (20, -9),
-            (14, -2),
-            (29, -7),
-            (13, -3),
-            (23, -5),
-            (24, -8),
-            (27, -9),
-            (30, -7),
-            (28, -5),
-            (21, -10),
-            (7, 9),
-            (6, 6),
-            (21, -5),
-            (27, -10),
-            (7, 2),
-            (30, -9),
-            (21, -8),
-            (22, -7),
-            (24, -9),
-            (20, -6),
-            (6, 9),
-            (29, -5),
-            (8, -2),
-            (27, -8),
-            (30, -5),
-            (24, -7),
-        ]
+        parse_lines(&example(17, "velocities"), |line| {
+            parse_all(line, coordinates)
+        })
+        .unwrap()
     }
 
     #[test]
     fn part_two() {
-        assert_eq!(2321, all_solutions((217, 240), (-126, -69)).len());
+        assert_eq!(
+            recorded(17, 2),
+            Day17::part_two(&Day17::parse(&real_input(17)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 }
@@ -12,6 +12,47 @@ use nom::sequence::{preceded, separated_pair, terminated};
 use nom::IResult;
 
 use crate::day18::NumberType::{PairNumber, Regular};
+use crate::error::{parse_all, parse_lines, Error, Result};
+use crate::solution::Solution;
+
+/// Day 18: Snailfish
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<String>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let numbers = parse_lines(input, |line| {
+            parse_checked(line.trim())?;
+            Ok(line.trim().to_string())
+        })?;
+        if numbers.is_empty() {
+            return Err(Error::invalid("there are no numbers to add"));
+        }
+        Ok(numbers)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(sum(input.iter().map(String::as_str)).magnitude())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        input
+            .iter()
+            .map(String::as_str)
+            .combinations(2)
+            .map(|mut two| {
+                let one = sum(two.iter().copied()).magnitude();
+                two.reverse();
+                let two = sum(two).magnitude();
+                i64::max(one, two)
+            })
+            .max()
+            .ok_or(Error::no_solution("adding two numbers needs at least two"))
+    }
+}
 
 type RcNumber = Rc<RefCell<Number>>;
 
@@ -29,14 +70,25 @@ impl Default for NumberType {
     }
 }
 
-#[derive(Debug, Default)]
-struct Number {
+#[derive(Default)]
+pub struct Number {
     id: i64,
     content: NumberType,
     depth: u64,
     parent: Option<RcNumber>,
 }
 
+//Leaves out the parent, which points back up the tree and would be followed forever
+impl fmt::Debug for Number {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Number")
+            .field("id", &self.id)
+            .field("content", &self.content)
+            .field("depth", &self.depth)
+            .finish_non_exhaustive()
+    }
+}
+
 impl Number {
     fn new_rc(content: NumberType) -> RcNumber {
         Rc::new(RefCell::new(Self::new(content)))
@@ -44,7 +96,6 @@ impl Number {
 
     fn new(content: NumberType) -> Self {
         let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
-        ();
         Self {
             id,
             content,
@@ -110,16 +161,8 @@ enum Action {
 fn needs_explosion(n: &RcNumber) -> bool {
     let parent = n.borrow().parent.as_ref().unwrap().clone();
     if let PairNumber(left, right) = &parent.borrow().content {
-        let left_reg = if let Regular(_) = &left.borrow().content {
-            true
-        } else {
-            false
-        };
-        let right_reg = if let Regular(_) = &right.borrow().content {
-            true
-        } else {
-            false
-        };
+        let left_reg = matches!(&left.borrow().content, Regular(_));
+        let right_reg = matches!(&right.borrow().content, Regular(_));
         return left_reg && right_reg && left.borrow().depth > 4;
     }
     false
@@ -185,21 +228,18 @@ fn reduce(root: RcNumber) -> RcNumber {
             }
         }
     }
-    return root;
+    root
 }
 
 fn magnitude(number: RcNumber) -> i64 {
-    match &number.borrow().content {
-        Regular(n) => *n,
-        PairNumber(l, r) => 3 * magnitude(l.clone()) + 2 * magnitude(r.clone()),
-    }
+    number.borrow().magnitude()
 }
 
-fn sum(numbers: Vec<String>) -> Number {
+fn sum<'a>(numbers: impl IntoIterator<Item = &'a str>) -> Number {
     numbers
-        .iter()
-        .map(|s| parse_pair_primary(s.as_str()))
-        .fold(None, |acc, next| add(acc, next))
+        .into_iter()
+        .map(parse_pair_primary)
+        .fold(None, add)
         .unwrap()
         .take()
 }
@@ -272,11 +312,37 @@ fn extract_value(left: RcNumber) -> i64 {
     if let Regular(l) = &left.borrow().content {
         *l
     } else {
-        println!("{}", left.borrow());
-        unreachable!()
+        unreachable!("expected a regular number, found {}", left.borrow())
     }
 }
 
+impl Number {
+    /// Parses a snailfish number such as `[[1,2],3]`.
+    pub fn parse(input: &str) -> Result<Number> {
+        Ok(parse_checked(input)?.take())
+    }
+
+    /// Adds up the numbers in order, reducing after every addition.
+    pub fn sum<'a>(numbers: impl IntoIterator<Item = &'a str>) -> Result<Number> {
+        let numbers: Vec<&str> = numbers.into_iter().collect();
+        if numbers.is_empty() {
+            return Err(Error::invalid("there are no numbers to add"));
+        }
+        for (i, number) in numbers.iter().enumerate() {
+            parse_checked(number).map_err(|e| e.on_line(i + 1))?;
+        }
+        Ok(sum(numbers))
+    }
+
+    pub fn magnitude(&self) -> i64 {
+        match &self.content {
+            Regular(n) => *n,
+            PairNumber(l, r) => 3 * magnitude(l.clone()) + 2 * magnitude(r.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
 impl Number {
     fn apply(self) -> Self {
         let wrapped = Rc::new(RefCell::new(self));
@@ -315,16 +381,28 @@ fn parse_pair(input: &str) -> IResult<&str, (RcNumber, RcNumber)> {
     ))
 }
 
+fn parse_checked(input: &str) -> Result<RcNumber> {
+    let (l, r) = parse_all(input, parse_pair)?;
+    Ok(Rc::new(RefCell::new(Number::new(PairNumber(l, r)))))
+}
+
+//Only for numbers that already went through parse_checked
 fn parse_pair_primary(input: &str) -> RcNumber {
-    let (_, (l, r)) = parse_pair(input).unwrap();
-    Rc::new(RefCell::new(Number::new(PairNumber(l, r))))
+    parse_checked(input).unwrap()
 }
 
 #[cfg(test)]
 mod test {
 
     use super::*;
-    use crate::reader::{read_lines_filter_ok, split_lines};
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
+    use crate::reader::numbered_lines;
+
+    fn lines(input: &str) -> Vec<&str> {
+        numbered_lines(input).map(|(_, line)| line.trim()).collect()
+    }
 
     #[test]
     fn parse() {
@@ -421,7 +499,7 @@ mod test {
     fn add_simple() {
         assert_eq!(
             "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
-            sum(split_lines(
+            sum(lines(
                 "[[[[4,3],4],4],[7,[[8,4],9]]]
     [1,1]"
             ))
@@ -430,7 +508,7 @@ mod test {
 
         assert_eq!(
             "[[[[1,1],[2,2]],[3,3]],[4,4]]",
-            sum(split_lines(
+            sum(lines(
                 "[1,1]
 [2,2]
 [3,3]
@@ -441,7 +519,7 @@ mod test {
 
         assert_eq!(
             "[[[[3,0],[5,3]],[4,4]],[5,5]]",
-            sum(split_lines(
+            sum(lines(
                 "[1,1]
                 [2,2]
                 [3,3]
@@ -453,7 +531,7 @@ mod test {
 
         assert_eq!(
             "[[[[5,0],[7,4]],[5,5]],[6,6]]",
-            sum(split_lines(
+            sum(lines(
                 "[1,1]
                 [2,2]
                 [3,3]
@@ -469,7 +547,7 @@ mod test {
     fn add_complex() {
         assert_eq!(
             "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]",
-            sum(split_lines(
+            sum(lines(
                 "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]
 [7,[[[3,7],[4,3]],[[6,3],[8,8]]]]"
             ))
@@ -478,7 +556,7 @@ mod test {
 
         assert_eq!(
             "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
-            sum(split_lines(
+            sum(lines(
                 "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]
 [7,[[[3,7],[4,3]],[[6,3],[8,8]]]]
 [[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]
@@ -495,36 +573,13 @@ mod test {
 
         assert_eq!(
             "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]",
-            sum(split_lines(
-                "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
-[[[5,[2,8]],4],[5,[[9,9],0]]]
-[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
-[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
-[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
-[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
-[[[[5,4],[7,7]],8],[[8,3],8]]
-[[9,3],[[9,9],[6,[4,9]]]]
-[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
-[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]",
-            ))
-            .to_string()
+            sum(lines(example(18, "example").trim_end())).to_string()
         );
     }
 
     #[test]
     fn part_one_small() {
-        let summed = Rc::new(RefCell::new(sum(split_lines(
-            "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
-[[[5,[2,8]],4],[5,[[9,9],0]]]
-[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
-[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
-[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
-[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
-[[[[5,4],[7,7]],8],[[8,3],8]]
-[[9,3],[[9,9],[6,[4,9]]]]
-[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
-[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]",
-        ))));
+        let summed = Rc::new(RefCell::new(sum(lines(example(18, "example").trim_end()))));
         assert_eq!(
             "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]",
             summed.borrow().to_string()
@@ -535,26 +590,31 @@ mod test {
     #[test]
     fn part_one() {
         assert_eq!(
-            4435,
-            magnitude(Rc::new(RefCell::new(sum(read_lines_filter_ok(
-                "input/day18"
-            )))))
+            recorded(18, 1),
+            Day18::part_one(&Day18::parse(&real_input(18)).unwrap())
+                .unwrap()
+                .to_string()
         );
     }
 
     #[test]
     fn part_two() {
-        let lines = read_lines_filter_ok("input/day18");
-        let combinations = lines.into_iter().combinations(2);
-        let max = combinations
-            .map(|mut two| {
-                let one = magnitude(Rc::new(RefCell::new(sum(two.clone()))));
-                two.reverse();
-                let two = magnitude(Rc::new(RefCell::new(sum(two))));
-                i64::max(one, two)
-            })
-            .max()
-            .unwrap();
-        assert_eq!(4802, max);
+        assert_eq!(
+            recorded(18, 2),
+            Day18::part_two(&Day18::parse(&real_input(18)).unwrap())
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        assert_eq!(
+            "parse error at line 2, column 6: unexpected \"]\"",
+            Day18::parse("[1,2]\n[3,4]]\n[5,")
+                .map(|_| ())
+                .unwrap_err()
+                .to_string()
+        );
     }
 }
@@ -1,21 +1,57 @@
 use crate::day19::Direction::*;
+use crate::error::{parse_all, Error, Result};
+use crate::grid::Point;
+use crate::parsers::{coordinates, header, triple};
+use crate::solution::Solution;
+use crate::{debug, trace};
 use itertools::Itertools;
-use nom::bytes::complete::tag;
-use nom::character::complete::{alphanumeric1, line_ending};
-use nom::character::streaming::space1;
-use nom::combinator::opt;
-use nom::multi::{many0, many1, separated_list1};
-use nom::sequence::{delimited, pair, terminated, tuple};
+use nom::branch::alt;
+use nom::character::complete::line_ending;
+use nom::combinator::{map, opt};
+use nom::multi::{many0, many1};
+use nom::sequence::{pair, terminated};
 use nom::IResult;
+use std::cell::OnceCell;
 use std::collections::HashSet;
-use std::fs;
-
-pub fn main() {
-    let input = fs::read_to_string("input/day19").unwrap();
-    let scanners = parse_primary(input.to_string());
-    let solution = Solution::solve(scanners, 12);
-    println!("Day 19 - 1 {}", solution.beacons.len());
-    println!("Day 19 - 2 {}", solution.max_distance());
+
+/// Day 19: Beacon Scanner
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Scanners;
+    type PartOne = usize;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Scanners {
+            scanners: parse_primary(input.to_string())?,
+            alignment: OnceCell::new(),
+        })
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(input.alignment()?.beacons.len())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(input.alignment()?.max_distance())
+    }
+}
+
+/// The parsed scanners, aligned once by whichever part needs them first.
+pub struct Scanners {
+    scanners: Vec<Scanner>,
+    alignment: OnceCell<Alignment>,
+}
+
+impl Scanners {
+    fn alignment(&self) -> Result<&Alignment> {
+        if let Some(alignment) = self.alignment.get() {
+            return Ok(alignment);
+        }
+        let alignment = Alignment::solve(self.scanners.clone(), 12)?;
+        Ok(self.alignment.get_or_init(|| alignment))
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -30,60 +66,34 @@ enum Direction {
 
 type Orientation = [Direction; 3];
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
-struct Point {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl Point {
-    #[allow(dead_code)]
-    fn manhattan_dist(&self, other: &Self) -> i64 {
-        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
-    }
-
-    fn translate(&self, other: &Self) -> Self {
-        Self {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
-    }
-
-    fn rotate(&self, ori: &Orientation) -> Self {
-        fn single_transform(p: &Point, direction: Direction) -> i64 {
-            match direction {
-                PosX => p.x,
-                NegX => -p.x,
-                PosY => p.y,
-                NegY => -p.y,
-                PosZ => p.z,
-                NegZ => -p.z,
-            }
-        }
-
-        Self {
-            x: single_transform(&self, ori[0]),
-            y: single_transform(&self, ori[1]),
-            z: single_transform(&self, ori[2]),
+fn rotate(p: Point, ori: &Orientation) -> Point {
+    fn single_transform(p: Point, direction: Direction) -> i64 {
+        match direction {
+            PosX => p.x,
+            NegX => -p.x,
+            PosY => p.y,
+            NegY => -p.y,
+            PosZ => p.z,
+            NegZ => -p.z,
         }
     }
 
-    pub fn new(x: i64, y: i64, z: i64) -> Self {
-        Point { x, y, z }
-    }
+    Point::new(
+        single_transform(p, ori[0]),
+        single_transform(p, ori[1]),
+        single_transform(p, ori[2]),
+    )
 }
 
+/// The scanners placed relative to the first one, with all the beacons they see.
 #[derive(Debug, Clone)]
-struct Solution {
+struct Alignment {
     scanners: Vec<Scanner>,
     beacons: HashSet<Point>,
 }
 
 #[derive(Debug, Clone)]
-struct Scanner {
-    #[allow(dead_code)]
+pub struct Scanner {
     name: String,
     beacons: Vec<Point>,
     position: Option<Point>,
@@ -93,13 +103,12 @@ impl Scanner {
     //Insert orientations here
     fn delta(&self, index: usize, ori: &Orientation) -> (Point, Vec<Point>) {
         let mut deltas = Vec::<Point>::new();
-        let reference = self.beacons[index].rotate(&ori);
+        let reference = rotate(self.beacons[index], ori);
         for i in 0..self.beacons.len() {
             if i == index {
                 continue;
             }
-            let rotated = self.beacons[i].rotate(ori);
-            let delta = rotated.translate(&reference);
+            let delta = rotate(self.beacons[i], ori) - reference;
             deltas.push(delta);
         }
         deltas.sort();
@@ -114,10 +123,7 @@ impl Scanner {
     ) -> Option<(Point, Vec<Point>)> {
         let orientations = make_orientations();
 
-        println!(
-            "Computing overlap between {:?} and {:?}",
-            self.name, other.name
-        );
+        trace!("computing overlap between {} and {}", self.name, other.name);
         let overlap = orientations
             .iter()
             .filter_map(|ori| {
@@ -134,7 +140,7 @@ impl Scanner {
                                 //Check if there are at least `overlap_threshold` new points
                                 let not_overlapping = overlap.len() - deltas_self.len();
                                 if other.beacons.len() - not_overlapping >= overlap_threshold {
-                                    let translation = other_reference.translate(&reference_self);
+                                    let translation = other_reference - reference_self;
                                     Some((other_reference, translation))
                                 } else {
                                     None
@@ -142,31 +148,26 @@ impl Scanner {
                             })
                             .next();
 
-                        match overlaps {
-                            Some((reference, translation)) => Some((ori, reference, translation)),
-                            None => None,
-                        }
+                        overlaps.map(|(reference, translation)| (ori, reference, translation))
                     })
                     .next()
             })
             .next();
-        let (ori, _other_reference, translation) = if let Some(overlap) = overlap {
-            overlap
-        } else {
-            return None;
-        };
+        let (ori, _other_reference, translation) = overlap?;
 
         //other reference is already translated into space of the first scanner
-        // println!("Overlaps {:?} {:?}", other_reference, ori);
+        trace!(
+            "{} overlaps {} in orientation {:?}",
+            other.name,
+            self.name,
+            ori
+        );
         let translated_points: Vec<Point> = other
             .beacons
             .iter()
-            .map(|b| {
-                let b = b.rotate(ori);
-                b.translate(&translation)
-            })
+            .map(|b| rotate(*b, ori) - translation)
             .collect();
-        // println!("Points {:?}", translated_points);
+        trace!("translated beacons {:?}", translated_points);
         Some((translation, translated_points))
     }
 
@@ -179,7 +180,7 @@ impl Scanner {
     }
 }
 
-impl Solution {
+impl Alignment {
     fn max_distance(&self) -> u64 {
         self.scanners
             .iter()
@@ -187,13 +188,15 @@ impl Solution {
             .combinations(2)
             .map(|points| points[0].manhattan_dist(&points[1]))
             .max()
-            .unwrap() as u64
+            .unwrap_or(0) as u64
     }
 
-    fn solve(mut scanners: Vec<Scanner>, overlap_threshold: usize) -> Self {
+    fn solve(mut scanners: Vec<Scanner>, overlap_threshold: usize) -> Result<Self> {
         //Weird pop front
         scanners.reverse();
-        let mut first = scanners.pop().unwrap();
+        let mut first = scanners
+            .pop()
+            .ok_or(Error::invalid("there are no scanners"))?;
         scanners.reverse();
         let beacons: HashSet<Point> = first.beacons.clone().into_iter().collect();
         first.position = Some(Point::new(0, 0, 0));
@@ -214,16 +217,15 @@ impl Solution {
                                 scanner_a.overlapping_beacons(scanner_b, overlap_threshold)
                             })
                             .next();
-                        match overlap {
-                            None => None,
-                            Some(overlap) => Some((overlap, scanner_b)),
-                        }
+                        overlap.map(|overlap| (overlap, scanner_b))
                     })
                     .next()
             };
 
-            //must have overlap
-            let ((translation, overlap), scanner_b) = overlap.unwrap();
+            let ((translation, overlap), scanner_b) = overlap.ok_or_else(|| {
+                let names = scanners.iter().map(|s| s.name.as_str()).join(", ");
+                Error::no_solution(format!("no overlap found for {}", names))
+            })?;
             solution.beacons.extend(overlap.iter());
             //Overlapping points are in 'scanner_a' translation, just add the relative translation of that scanner
             let solved_scanner_b = Scanner {
@@ -233,10 +235,14 @@ impl Solution {
             };
             scanners.retain(|s| s.name != solved_scanner_b.name);
             solution.scanners.push(solved_scanner_b);
-            println!("Scanners {:?}", scanners);
+            debug!(
+                "{} scanners solved, {} remaining",
+                solution.scanners.len(),
+                scanners.len()
+            );
         }
 
-        solution
+        Ok(solution)
     }
 }
 
@@ -270,19 +276,12 @@ fn make_orientations() -> Vec<Orientation> {
     ]
 }
 
+//"-618,-824,-621", or "0,2" for the 2D examples
 fn parse_beacon(input: &str) -> IResult<&str, Point> {
-    let (input, mut coords) = separated_list1(tag(","), nom::character::complete::i64)(input)?;
-    if coords.len() == 2 {
-        coords.push(0);
-    }
-    Ok((
-        input,
-        Point {
-            x: coords[0],
-            y: coords[1],
-            z: coords[2],
-        },
-    ))
+    alt((
+        map(triple, |(x, y, z)| Point { x, y, z }),
+        map(coordinates, |(x, y)| Point { x, y, z: 0 }),
+    ))(input)
 }
 
 fn parse_beacons(input: &str) -> IResult<&str, Vec<Point>> {
@@ -291,12 +290,7 @@ fn parse_beacons(input: &str) -> IResult<&str, Vec<Point>> {
 }
 
 fn parse_header(input: &str) -> IResult<&str, String> {
-    let (input, (name, _, number)) = delimited(
-        tag("--- "),
-        tuple((alphanumeric1, space1, alphanumeric1)),
-        pair(tag(" ---"), line_ending),
-    )(input)?;
-    Ok((input, format!("{} {}", name, number)))
+    map(terminated(header, line_ending), String::from)(input)
 }
 
 fn parse_scanners(input: &str) -> IResult<&str, Vec<Scanner>> {
@@ -313,15 +307,17 @@ fn parse_scanners(input: &str) -> IResult<&str, Vec<Scanner>> {
             .collect(),
     ))
 }
-fn parse_primary(input: String) -> Vec<Scanner> {
-    let (_, scanners) = parse_scanners(input.as_str()).unwrap();
-    scanners
+fn parse_primary(input: String) -> Result<Vec<Scanner>> {
+    parse_all(input.as_str(), parse_scanners)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs;
+
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
     #[test]
     fn part_one_small_no_rotate() {
         let input = "--- scanner 0 ---
@@ -333,10 +329,10 @@ mod test {
 -1,-1
 -5,0
 -2,1";
-        let scanners = parse_primary(input.to_string());
+        let scanners = parse_primary(input.to_string()).unwrap();
         let overlapping_beacons = scanners[0].overlapping_beacons(&scanners[1], 3);
         assert_eq!(3, overlapping_beacons.unwrap().1.len());
-        let solution = Solution::solve(scanners, 3);
+        let solution = Alignment::solve(scanners, 3).unwrap();
         assert_eq!(3, solution.beacons.len())
     }
 
@@ -354,7 +350,7 @@ mod test {
 
         let transformed = orientations
             .iter()
-            .map(|o| point.rotate(o))
+            .map(|o| rotate(point, o))
             .collect::<Vec<_>>();
 
         let expected = vec![
@@ -390,7 +386,7 @@ mod test {
 
         let p = Point { x: 2, y: -1, z: 3 };
         let r = [NegX, NegZ, NegY];
-        assert_eq!(Point::new(-2, -3, 1), p.rotate(&r));
+        assert_eq!(Point::new(-2, -3, 1), rotate(p, &r));
     }
 
     #[test]
@@ -411,9 +407,9 @@ mod test {
 -5,4,-6
 -8,-7,0";
 
-        let mut scanners = parse_primary(input.to_string());
+        let mut scanners = parse_primary(input.to_string()).unwrap();
         scanners.reverse();
-        let solution = Solution::solve(scanners, 6);
+        let solution = Alignment::solve(scanners, 6).unwrap();
         assert_eq!(6, solution.beacons.len());
     }
 
@@ -458,34 +454,44 @@ mod test {
 3,1,2
 -6,-4,-5
 0,7,-8";
-        let scanners = parse_primary(input.to_string());
-        let solution = Solution::solve(scanners, 6);
+        let scanners = parse_primary(input.to_string()).unwrap();
+        let solution = Alignment::solve(scanners, 6).unwrap();
         assert_eq!(6, solution.beacons.len());
     }
 
     #[test]
     fn part_one_small() {
-        let input = fs::read_to_string("input/day19-small").unwrap();
-        let scanners = parse_primary(input.to_string());
-        let solution = Solution::solve(scanners, 12);
+        let input = example(19, "scanners");
+        let scanners = parse_primary(input.to_string()).unwrap();
+        let solution = Alignment::solve(scanners, 12).unwrap();
         println!("solution: {:?}", solution);
         assert_eq!(79, solution.beacons.len());
         assert_eq!(3621, solution.max_distance());
     }
+
+    #[test]
+    fn aligned_once() {
+        let scanners = Day19::parse(&example(19, "scanners")).unwrap();
+        assert!(scanners.alignment.get().is_none());
+        assert_eq!(Ok(79), Day19::part_one(&scanners));
+        assert!(scanners.alignment.get().is_some());
+        assert_eq!(Ok(3621), Day19::part_two(&scanners));
+    }
+
     #[test]
     fn part_one_small02() {
-        let input = fs::read_to_string("input/day19-small02").unwrap();
-        let scanners = parse_primary(input.to_string());
-        let solution = Solution::solve(scanners, 12);
+        let input = example(19, "overlap");
+        let scanners = parse_primary(input.to_string()).unwrap();
+        let solution = Alignment::solve(scanners, 12).unwrap();
 
         assert_eq!(39, solution.beacons.len());
     }
     #[test]
     fn part_one_two() {
-        let input = fs::read_to_string("input/day19").unwrap();
-        let scanners = parse_primary(input.to_string());
-        let solution = Solution::solve(scanners, 12);
-        assert_eq!(438, solution.beacons.len());
-        assert_eq!(3621, solution.max_distance());
+        let input = real_input(19);
+        let scanners = parse_primary(input.to_string()).unwrap();
+        let solution = Alignment::solve(scanners, 12).unwrap();
+        assert_eq!(recorded(19, 1), solution.beacons.len().to_string());
+        assert_eq!(recorded(19, 2), solution.max_distance().to_string());
     }
 }
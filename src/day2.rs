@@ -1,30 +1,35 @@
 use std::str::FromStr;
 
-use crate::reader::read_lines;
+use nom::character::complete::{alpha1, space1};
+use nom::sequence::separated_pair;
 
-pub fn main() {
-    calc_one();
-    calc_two();
-}
+use crate::error::{parse_all, parse_lines, Error, Result};
+use crate::parsers::integer;
+use crate::solution::Solution;
 
-fn calc_one() {
-    let line_iter = read_lines("input/day2").unwrap();
-    let (forward, depth) = line_iter
-        .filter_map(|s| s.unwrap().parse::<Instruction>().ok())
-        .fold((0i32, 0i32), |sum, val| (sum.0 + val.x, sum.1 + val.y));
+/// Day 2: Dive!
+pub struct Day2;
 
-    println!("Forward {} Depth {}", forward, depth);
-    println!("Solution 1: {}", forward * depth);
-}
+impl Solution for Day2 {
+    type Input = Vec<Instruction>;
+    type PartOne = i32;
+    type PartTwo = i32;
 
-fn calc_two() {
-    let line_iter = read_lines("input/day2").unwrap();
-    let (forward, depth, aim) = line_iter
-        .filter_map(|s| s.unwrap().parse::<Instruction>().ok())
-        .fold((0i32, 0i32, 0i32), |val, i| apply_instruction(val, &i));
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input, str::parse)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        let (forward, depth) = input
+            .iter()
+            .fold((0i32, 0i32), |sum, val| (sum.0 + val.x, sum.1 + val.y));
+        Ok(forward * depth)
+    }
 
-    println!("Forward {} Depth {} Aim {}", forward, depth, aim);
-    println!("Solution 2: {}", forward * depth);
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        let (forward, depth, _) = input.iter().fold((0i32, 0i32, 0i32), apply_instruction);
+        Ok(forward * depth)
+    }
 }
 
 fn apply_instruction((x, y, aim): (i32, i32, i32), instruction: &Instruction) -> (i32, i32, i32) {
@@ -32,12 +37,6 @@ fn apply_instruction((x, y, aim): (i32, i32, i32), instruction: &Instruction) ->
     (x + instruction.x, y + aim * instruction.x, aim)
 }
 
-enum Day2Err {
-    InvalidDirection,
-    InvalidIncrement,
-    InvalidInput,
-}
-
 #[derive(Debug)]
 enum Direction {
     Forward,
@@ -47,37 +46,32 @@ enum Direction {
 }
 
 impl FromStr for Direction {
-    type Err = Day2Err;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> Result<Self> {
         match s {
             "forward" => Ok(Self::Forward),
             "backward" => Ok(Self::Backward),
             "up" => Ok(Self::Up),
             "down" => Ok(Self::Down),
-            _ => Err(Day2Err::InvalidDirection),
+            _ => Err(Error::parse(format!("unknown direction {:?}", s))),
         }
     }
 }
 
 #[derive(Debug)]
-struct Instruction {
+pub struct Instruction {
     pub x: i32,
     pub y: i32,
 }
 
 impl FromStr for Instruction {
-    type Err = Day2Err;
+    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let chunks: Vec<&str> = s.split(" ").collect();
-        if chunks.len() != 2 {
-            return Err(Day2Err::InvalidInput);
-        }
-        let direction = Direction::from_str(chunks[0])?;
-        let increment = chunks[1]
-            .parse::<i32>()
-            .map_err(|_| Day2Err::InvalidIncrement)?;
+    fn from_str(s: &str) -> Result<Self> {
+        //"forward 5"
+        let (direction, increment) = parse_all(s, separated_pair(alpha1, space1, integer::<i32>))?;
+        let direction = Direction::from_str(direction)?;
 
         let (x, y) = match direction {
             Direction::Forward => (increment, 0),
@@ -95,7 +89,7 @@ mod tests {
 
     #[test]
     fn part_two() {
-        let input = vec![
+        let input = [
             Instruction { x: 5, y: 0 },
             Instruction { x: 0, y: 5 },
             Instruction { x: 8, y: 0 },
@@ -1,23 +1,50 @@
-use crate::neighbors::GEN_NEIGHBORS_SELF;
-use itertools::Itertools;
-use nom::branch::alt;
-use nom::bytes::complete::tag;
-use nom::character::complete::line_ending;
-use nom::combinator::opt;
-use nom::multi::{count, many0, many1};
-use nom::sequence::terminated;
+use crate::error::{parse_all, Error, Result};
+use crate::grid::{Edges, Neighborhood, Plane};
+use crate::reader::{paragraphs, parse_char_grid};
+use crate::solution::Solution;
+use nom::character::complete::anychar;
+use nom::combinator::map_opt;
+use nom::multi::count;
 use nom::IResult;
-use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Formatter;
 
-#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+/// Day 20: Trench Map
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Image;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_image(input)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        enhance(input, 2).count()
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        enhance(input, 50).count()
+    }
+}
+
+fn enhance(image: &Image, times: usize) -> Image {
+    let mut image = image.process();
+    for _ in 1..times {
+        image = image.process();
+    }
+    image
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 enum Pixel {
     On,
     Off,
 }
 
-impl fmt::Debug for Pixel {
+impl fmt::Display for Pixel {
     #[rustfmt::skip]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", match &self {
@@ -27,258 +54,180 @@ impl fmt::Debug for Pixel {
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
-struct Point {
-    x: i64,
-    y: i64,
-}
-
-struct Image {
+/// The image is lit only where the grid says so, unless its plane says otherwise beyond it.
+pub struct Image {
     algo: Vec<Pixel>,
-    grid: HashSet<Point>,
-    outside: Pixel,
-    dims: Dims,
-}
-
-#[derive(Debug)]
-enum Day20Error {
-    NotBinaryNumber,
-    InvalidGrid,
-    InfiniteNumber,
+    plane: Plane<Pixel>,
 }
 
 impl fmt::Debug for Image {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "Image {{ ")?;
-        write!(f, "outside: {:?},\n ", self.outside)?;
-        write!(f, "dims: {:?},\n ", self.dims)?;
+        write!(f, "edges: {:?},\n ", self.plane.edges())?;
+        write!(f, "origin: {:?},\n ", self.plane.origin())?;
         write!(f, "algo: {:?},\n ", self.algo)?;
-        write!(f, "grid: \n")?;
-        for y in self.dims.min_y..=self.dims.max_y {
-            for x in self.dims.min_x..=self.dims.max_x {
-                let val = if self.grid.contains(&Point { x, y }) {
-                    Pixel::On
-                } else {
-                    Pixel::Off
-                };
-                write!(f, "{:?}", val)?;
-            }
-            write!(f, "\n")?;
-        }
+        writeln!(f, "grid: ")?;
+        writeln!(f, "{}", self.plane.grid())?;
         write!(f, "}}")
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Dims {
-    min_x: i64,
-    max_x: i64,
-    min_y: i64,
-    max_y: i64,
-}
-
-impl Dims {
-    fn contains(&self, p: &Point) -> bool {
-        p.x >= self.min_x && p.x <= self.max_x && p.y >= self.min_y && p.y <= self.max_y
-    }
-
-    fn from(grid: &HashSet<Point>, extra: i64) -> Dims {
-        let max_x = grid
-            .iter()
-            .max_by_key(|p| p.x)
-            .ok_or(Day20Error::InvalidGrid)
-            .unwrap()
-            .x
-            + extra;
-        let min_y = grid
-            .iter()
-            .min_by_key(|p| p.y)
-            .ok_or(Day20Error::InvalidGrid)
-            .unwrap()
-            .y
-            - extra;
-        let min_x = grid
-            .iter()
-            .min_by_key(|p| p.x)
-            .ok_or(Day20Error::InvalidGrid)
-            .unwrap()
-            .x
-            - extra;
-        let max_y = grid
-            .iter()
-            .max_by_key(|p| p.y)
-            .ok_or(Day20Error::InvalidGrid)
-            .unwrap()
-            .y
-            + extra;
-        Dims {
-            min_x,
-            max_x,
-            min_y,
-            max_y,
-        }
-    }
-}
-
 impl Image {
-    fn read_pixel(&self, pos: &Point) -> Result<usize, Day20Error> {
-        let offset_string: String = GEN_NEIGHBORS_SELF
+    fn read_pixel(plane: &Plane<Pixel>, (x, y): (i32, i32)) -> usize {
+        //Not through Plane::neighbors, as every pixel beyond a bounded plane still counts as off
+        Neighborhood::EightWithSelf
+            .offsets()
             .iter()
-            .map(|(o_x, o_y)| Point {
-                x: pos.x + *o_x as i64,
-                y: pos.y + *o_y as i64,
-            })
-            .map(|p| {
-                //Store only ones
-                if self.grid.contains(&p) {
-                    '1'
-                } else {
-                    if self.dims.contains(&p) {
-                        '0'
-                    } else {
-                        match self.outside {
-                            Pixel::On => '1',
-                            Pixel::Off => '0',
-                        }
-                    }
-                }
+            .map(|(o_x, o_y)| plane.get((x + *o_x as i32, y + *o_y as i32)))
+            .map(|p| match p {
+                Some(Pixel::On) => 1,
+                Some(Pixel::Off) | None => 0,
             })
-            .collect();
-
-        let result = usize::from_str_radix(offset_string.as_str(), 2)
-            .map_err(|_| Day20Error::NotBinaryNumber)?;
-        Ok(result)
+            .fold(0, |index, bit| index << 1 | bit)
     }
 
-    fn process(&self) -> Result<Self, Day20Error> {
-        let mut grid = HashSet::<Point>::new();
-
-        for y in self.dims.min_y - 1..=self.dims.max_y + 1 {
-            for x in self.dims.min_x - 1..=self.dims.max_x + 1 {
-                let p = Point { x, y };
-                let index = self.read_pixel(&p)?;
-                let val = self.algo[index];
-                if val == Pixel::On {
-                    grid.insert(p);
-                }
-            }
-        }
-        let dims = Dims::from(&grid, 0);
-        Ok(Self {
+    fn process(&self) -> Self {
+        //Pixels next to the grid of an infinite plane can be lit by it, so grow to include them
+        let plane = self.plane.grow(1);
+        let edges = match plane.edges() {
+            Edges::Infinite(Pixel::Off) => Edges::Infinite(self.algo[0]),
+            Edges::Infinite(Pixel::On) => Edges::Infinite(self.algo[511]),
+            edges => edges.clone(),
+        };
+        Self {
             algo: self.algo.clone(),
-            grid,
-            outside: if self.outside == Pixel::Off {
-                self.algo[0]
-            } else {
-                self.algo[511]
-            },
-            dims,
-        })
+            plane: plane.next(edges, |pos| self.algo[Self::read_pixel(&plane, pos)]),
+        }
     }
 
-    fn count(&self) -> Result<usize, Day20Error> {
-        match self.outside {
-            Pixel::On => Err(Day20Error::InfiniteNumber),
-            Pixel::Off => Ok(self.grid.len()),
+    fn count(&self) -> Result<usize> {
+        match self.plane.edges() {
+            Edges::Infinite(Pixel::On) => Err(Error::no_solution("infinitely many pixels are lit")),
+            _ => Ok(self
+                .plane
+                .grid()
+                .cells()
+                .iter()
+                .filter(|p| **p == Pixel::On)
+                .count()),
         }
     }
 }
 
-fn parse_pixel(input: &str) -> IResult<&str, Pixel> {
-    fn parse_on(input: &str) -> IResult<&str, Pixel> {
-        let (input, _) = tag("#")(input)?;
-        Ok((input, Pixel::On))
+fn pixel(c: char) -> Option<Pixel> {
+    match c {
+        '#' => Some(Pixel::On),
+        '.' => Some(Pixel::Off),
+        _ => None,
     }
-    fn parse_off(input: &str) -> IResult<&str, Pixel> {
-        let (input, _) = tag(".")(input)?;
-        Ok((input, Pixel::Off))
-    }
-    alt((parse_on, parse_off))(input)
 }
 
 fn parse_algo(input: &str) -> IResult<&str, Vec<Pixel>> {
-    count(parse_pixel, 512)(input)
+    count(map_opt(anychar, pixel), 512)(input)
 }
 
-fn parse_input(input: &str) -> IResult<&str, Image> {
-    let (input, algo) = terminated(parse_algo, many0(line_ending))(input)?;
-    let (input, grid) = many1(terminated(many1(parse_pixel), opt(line_ending)))(input)?;
-    let mut output_grid = HashSet::<Point>::new();
-    for y in 0i64..grid.len() as i64 {
-        let line = &grid[y as usize];
-        output_grid.extend(
-            line.iter()
-                .positions(|p| *p == Pixel::On)
-                .map(|pos| Point { x: pos as i64, y }),
-        );
-    }
-    let dims = Dims::from(&output_grid, 0);
-    Ok((
-        input,
-        Image {
-            algo,
-            grid: output_grid,
-            outside: Pixel::Off,
-            dims,
-        },
-    ))
+fn parse_image(input: &str) -> Result<Image> {
+    let mut sections = paragraphs(input);
+    let (algo, image) = match (sections.next(), sections.next(), sections.next()) {
+        (Some(algo), Some(image), None) => (algo, image),
+        (_, _, Some(extra)) => {
+            return Err(Error::invalid("expected nothing after the image").on_line(extra.line))
+        }
+        _ => return Err(Error::invalid("expected both an algorithm and an image")),
+    };
+    let algo = parse_all(algo.text, parse_algo).map_err(|e| e.on_line(algo.line))?;
+    let grid =
+        parse_char_grid(image.text, "'#' or '.'", pixel).map_err(|e| e.on_line(image.line))?;
+    Ok(Image {
+        algo,
+        plane: Plane::new(grid, Edges::Infinite(Pixel::Off)),
+    })
 }
 
 #[cfg(test)]
 mod test {
+    use crate::answers::recorded;
+    use crate::examples::example;
+    use crate::input::real_input;
+
     use super::*;
-    use std::fs;
     #[test]
     fn part_one_small() {
-        let input = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
-
-#..#.
-#....
-##..#
-..#..
-..###";
-        let (_, input) = parse_input(input).unwrap();
+        let input = example(20, "example");
+        let input = parse_image(&input).unwrap();
         assert_eq!(Pixel::Off, input.algo[0]);
         assert_eq!(Pixel::On, input.algo[34]);
         assert_eq!(Pixel::On, input.algo[50]);
-        let processed = input.process().unwrap();
-        let processed = processed.process().unwrap();
-        assert_eq!(35, processed.grid.len());
+        let processed = input.process();
+        let processed = processed.process();
+        assert_eq!(35, processed.count().unwrap());
     }
 
     #[test]
     fn part_one() {
-        let input = fs::read_to_string("input/day20").unwrap();
-        let (_, image) = parse_input(input.as_str()).unwrap();
-        let image = image.process().unwrap();
+        let input = real_input(20);
+        let image = parse_image(&input).unwrap();
+        let image = image.process();
         assert!(image.count().is_err());
-        let image = image.process().unwrap();
-        assert_eq!(5229, image.count().unwrap());
+        let image = image.process();
+        assert_eq!(recorded(20, 1), image.count().unwrap().to_string());
     }
 
     #[test]
     fn part_two_small() {
-        let input = "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
-
-#..#.
-#....
-##..#
-..#..
-..###";
-        let (_, mut input) = parse_input(input).unwrap();
+        let input = example(20, "example");
+        let mut input = parse_image(&input).unwrap();
         for _ in 0..50 {
-            input = input.process().unwrap();
+            input = input.process();
         }
         assert_eq!(3351, input.count().unwrap());
     }
 
     #[test]
     fn part_two() {
-        let input = fs::read_to_string("input/day20").unwrap();
-        let (_, mut image) = parse_input(input.as_str()).unwrap();
-        for _ in 0..50 {
-            image = image.process().unwrap();
-        }
-        assert_eq!(17009, image.count().unwrap());
+        assert_eq!(
+            recorded(20, 2),
+            Day20::part_two(&Day20::parse(&real_input(20)).unwrap())
+                .unwrap()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn topologies() {
+        //Any lit pixel lights up everything around it
+        let algo = format!(".{}", "#".repeat(511));
+        let image = |edges| {
+            let image = parse_image(&format!("{}\n\n#..\n...\n...", algo)).unwrap();
+            //The puzzle itself is on an infinite plane that is off beyond the grid
+            let plane = Plane::new(image.plane.grid().clone(), edges);
+            Image { plane, ..image }.process()
+        };
+        assert_eq!(
+            "##.\n##.\n...",
+            image(Edges::Bounded).plane.grid().to_string()
+        );
+        assert_eq!(9, image(Edges::Wrapping).count().unwrap());
+        let infinite = image(Edges::Infinite(Pixel::Off));
+        assert_eq!((-1, -1), infinite.plane.origin());
+        assert_eq!(
+            "###..\n###..\n###..\n.....\n.....",
+            infinite.plane.grid().to_string()
+        );
+        assert!(infinite.process().process().count().is_ok());
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let input = format!("{}\n\n#..\n..\n...", ".".repeat(512));
+        assert_eq!(
+            "invalid input at line 4: expected 3 columns like the first row, found 2",
+            parse_image(&input).unwrap_err().to_string()
+        );
+        let input = format!("{}\n\n#..\n.x.", ".".repeat(512));
+        assert_eq!(
+            "parse error at line 4, column 2: expected '#' or '.', found 'x'",
+            parse_image(&input).unwrap_err().to_string()
+        );
     }
 }
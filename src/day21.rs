@@ -1,9 +1,53 @@
-use itertools::{iproduct, Itertools};
 use std::collections::HashMap;
 
-pub fn main() {
-    let (one_wins, two_wins) = simulate(4, 8, 21);
-    println!("Day 21 - 2: 1 wins: {} 2 wins {}", one_wins, two_wins);
+use nom::bytes::complete::tag;
+use nom::character::complete::{line_ending, u64};
+use nom::sequence::{delimited, preceded, separated_pair};
+use nom::IResult;
+
+use crate::error::{parse_all, Error, Result};
+use crate::solution::Solution;
+
+/// Day 21: Dirac Dice
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = (u64, u64);
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let positions = parse_all(input, parse_positions)?;
+        if ![positions.0, positions.1]
+            .iter()
+            .all(|p| (1..=10).contains(p))
+        {
+            return Err(Error::invalid(
+                "starting positions must be between 1 and 10",
+            ));
+        }
+        Ok(positions)
+    }
+
+    fn part_one((p1_start, p2_start): &Self::Input) -> Result<Self::PartOne> {
+        Ok(play_game(*p1_start, *p2_start))
+    }
+
+    fn part_two((p1_start, p2_start): &Self::Input) -> Result<Self::PartTwo> {
+        let (one_wins, two_wins) = simulate(*p1_start, *p2_start, 21);
+        Ok(u64::max(one_wins, two_wins))
+    }
+}
+
+fn parse_position(input: &str) -> IResult<&str, u64> {
+    preceded(
+        delimited(tag("Player "), u64, tag(" starting position: ")),
+        u64,
+    )(input)
+}
+
+fn parse_positions(input: &str) -> IResult<&str, (u64, u64)> {
+    separated_pair(parse_position, line_ending, parse_position)(input)
 }
 
 static THREE_ROLLS: [u8; 27] = [
@@ -48,7 +92,7 @@ impl Die {
 
     fn practice_roll(&mut self) -> u64 {
         let next_roll = self.num_rolls % 100 + 1;
-        self.num_rolls = self.num_rolls + 1u64;
+        self.num_rolls += 1u64;
         next_roll
     }
 }
@@ -66,13 +110,6 @@ struct GameState {
 }
 
 fn simulate(p1_start: u64, p2_start: u64, final_score: u64) -> (u64, u64) {
-    let all_rolls = THREE_ROLLS
-        .iter()
-        .cartesian_product(THREE_ROLLS.iter())
-        .collect::<Vec<_>>();
-
-    println!("all rolls {}", all_rolls.len());
-
     type GameMap = HashMap<GameState, u64>;
     let mut player_one_wins = 0u64;
     let mut player_two_wins = 0u64;
@@ -90,24 +127,25 @@ fn simulate(p1_start: u64, p2_start: u64, final_score: u64) -> (u64, u64) {
     let mut games = GameMap::new();
     games.insert(initial_state, 1);
     while !games.is_empty() {
-        let cur_games = games.clone().into_iter().collect::<Vec<_>>();
-        // println!("Cur games {:?}", cur_games);
-        games.clear();
+        let cur_games = std::mem::take(&mut games);
 
         for (game, count) in cur_games.into_iter() {
-            if game.player_1.score >= final_score {
-                player_one_wins += count;
-                continue;
-            }
-            if game.player_2.score >= final_score {
-                player_two_wins += count;
-                continue;
-            }
-            for (p1_move, p2_move) in all_rolls.iter() {
-                let player_1 = move_player(game.player_1, **p1_move);
-                let player_2 = move_player(game.player_2, **p2_move);
-                let state = GameState { player_1, player_2 };
-                *games.entry(state).or_insert(0) += count;
+            //Player 2 only gets to move in the universes where player 1 didn't win yet
+            for p1_move in THREE_ROLLS.iter() {
+                let player_1 = move_player(game.player_1, *p1_move as u64);
+                if player_1.score >= final_score {
+                    player_one_wins += count;
+                    continue;
+                }
+                for p2_move in THREE_ROLLS.iter() {
+                    let player_2 = move_player(game.player_2, *p2_move as u64);
+                    if player_2.score >= final_score {
+                        player_two_wins += count;
+                        continue;
+                    }
+                    let state = GameState { player_1, player_2 };
+                    *games.entry(state).or_insert(0) += count;
+                }
             }
         }
     }
@@ -115,8 +153,8 @@ fn simulate(p1_start: u64, p2_start: u64, final_score: u64) -> (u64, u64) {
     (player_one_wins, player_two_wins)
 }
 
-fn move_player(mut player: Player, dist: u8) -> Player {
-    player.pos = (player.pos + dist) % 10;
+fn move_player(mut player: Player, dist: u64) -> Player {
+    player.pos = ((player.pos as u64 + dist) % 10) as u8;
     player.score += player.pos as u64 + 1;
     player
 }
@@ -140,22 +178,38 @@ fn play_game(p1_start: u64, p2_start: u64) -> u64 {
 
     loop {
         let move_1 = roll_three_times(&mut die);
-        player_1 = move_player(player_1, move_1 as u8);
+        player_1 = move_player(player_1, move_1);
         if player_1.score >= 1000 {
-            return player_2.score as u64 * &die.num_rolls;
+            return player_2.score * die.num_rolls;
         }
 
         let move_2 = roll_three_times(&mut die);
-        player_2 = move_player(player_2, move_2 as u8);
+        player_2 = move_player(player_2, move_2);
         if player_2.score >= 1000 {
-            return player_1.score as u64 * &die.num_rolls;
+            return player_1.score * die.num_rolls;
         }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::answers::recorded;
+    use crate::input::real_input;
+
     use super::*;
+
+    #[test]
+    fn parse() {
+        let input = "Player 1 starting position: 4\nPlayer 2 starting position: 8\n";
+        assert_eq!(Ok((4, 8)), Day21::parse(input));
+        assert_eq!(
+            "invalid input: starting positions must be between 1 and 10",
+            Day21::parse("Player 1 starting position: 4\nPlayer 2 starting position: 11")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
     #[test]
     fn part_one_small() {
         let mut die = Die::new();
@@ -171,7 +225,12 @@ mod test {
 
     #[test]
     fn part_one() {
-        assert_eq!(518418, play_game(8, 1));
+        assert_eq!(
+            recorded(21, 1),
+            Day21::part_one(&Day21::parse(&real_input(21)).unwrap())
+                .unwrap()
+                .to_string()
+        );
     }
 
     #[test]
@@ -184,9 +243,10 @@ mod test {
 
     #[test]
     fn part_two() {
-        let (one_wins, two_wins) = simulate(8, 1, 21);
+        let (p1_start, p2_start) = Day21::parse(&real_input(21)).unwrap();
+        let (one_wins, two_wins) = simulate(p1_start, p2_start, 21);
         println!("One wins {} two wins {}", one_wins, two_wins);
-        assert_eq!(444356092776315, one_wins);
-        assert_eq!(341960390180808, two_wins);
+        assert_eq!(113467910521040, one_wins);
+        assert_eq!(recorded(21, 2), two_wins.to_string());
     }
 }
@@ -1,27 +1,38 @@
-use crate::reader::read_lines;
-
-pub fn main() {
-    let lines: Vec<String> = read_lines("input/day3")
-        .unwrap()
-        .filter_map(|l| l.ok())
-        .collect();
-    let lines: Vec<&str> = lines.iter().map(AsRef::as_ref).collect();
-    let (gamma, epsilon) = compute_gamma_epsilon(&lines);
-    println!("Solution day 3-1 {}", gamma * epsilon);
-
-    let (num_chars, ints) = parse_input(&lines[..]);
-    let (oxygen, co2) = (
-        compute_oxygen(&ints[..], num_chars),
-        compute_co2_scrubber(&ints[..], num_chars),
-    );
-    println!("Solution day 3-2 {}", oxygen * co2);
+use crate::error::{Error, Result};
+use crate::solution::Solution;
+
+/// Day 3: Binary Diagnostic
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = (usize, Vec<u32>);
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines: Vec<&str> = input.lines().take_while(|l| !l.is_empty()).collect();
+        parse_input(&lines)
+    }
+
+    fn part_one((num_chars, ints): &Self::Input) -> Result<Self::PartOne> {
+        let (gamma, epsilon) = compute_gamma_epsilon(*num_chars, ints);
+        Ok(gamma * epsilon)
+    }
+
+    fn part_two((num_chars, ints): &Self::Input) -> Result<Self::PartTwo> {
+        let (oxygen, co2) = (
+            compute_oxygen(ints, *num_chars)?,
+            compute_co2_scrubber(ints, *num_chars)?,
+        );
+        Ok(oxygen * co2)
+    }
 }
 
-fn compute_oxygen(inputs: &[u32], num_chars: usize) -> u32 {
+fn compute_oxygen(inputs: &[u32], num_chars: usize) -> Result<u32> {
     compute_diagnostic(inputs, true, num_chars)
 }
 
-fn compute_co2_scrubber(inputs: &[u32], num_chars: usize) -> u32 {
+fn compute_co2_scrubber(inputs: &[u32], num_chars: usize) -> Result<u32> {
     compute_diagnostic(inputs, false, num_chars)
 }
 
@@ -32,10 +43,12 @@ fn count_ones_at(inputs: &[u32], bit: usize) -> u32 {
         .count() as u32
 }
 
-fn compute_diagnostic(inputs: &[u32], ones_and_common: bool, num_chars: usize) -> u32 {
+fn compute_diagnostic(inputs: &[u32], ones_and_common: bool, num_chars: usize) -> Result<u32> {
     let mut inputs = Vec::from(inputs);
-    let mut index = num_chars - 1;
-    while inputs.len() > 1 {
+    for index in (0..num_chars).rev() {
+        if inputs.len() <= 1 {
+            break;
+        }
         let ones = count_ones_at(&inputs, index);
         let zeroes = inputs.len() as u32 - ones;
         let keep_value = match (ones, zeroes, ones_and_common) {
@@ -46,48 +59,52 @@ fn compute_diagnostic(inputs: &[u32], ones_and_common: bool, num_chars: usize) -
 
         inputs.retain(|line| {
             let bit_at = get_bit_at(*line, index);
-            let retained = bit_at == keep_value;
-            retained
+
+            bit_at == keep_value
         });
-        if index > 0 {
-            index -= 1;
-        }
     }
-    inputs[0]
+    match inputs[..] {
+        [rating] => Ok(rating),
+        _ => Err(Error::no_solution("the report has no unique rating")),
+    }
 }
 
-fn compute_gamma_epsilon(input: &[&str]) -> (u32, u32) {
-    let (num_chars, ints) = parse_input(input);
-
+fn compute_gamma_epsilon(num_chars: usize, ints: &[u32]) -> (u32, u32) {
     let mut counts = vec![0u32; num_chars];
     for number in ints {
-        for i in 0..num_chars {
-            counts[i] += get_bit_at(number, num_chars - 1 - i);
+        for (i, count) in counts.iter_mut().enumerate() {
+            *count += get_bit_at(*number, num_chars - 1 - i);
         }
     }
     let mut index = (num_chars - 1) as u32;
     let mut gamma = 0u32;
-    let threshold: u32 = (input.len() / 2) as u32;
+    let threshold: u32 = (ints.len() / 2) as u32;
     for count in counts.iter() {
         let bit = if count > &threshold { 1 } else { 0 };
         gamma |= bit << index;
-        if index > 0 {
-            index -= 1;
-        }
+        index = index.saturating_sub(1);
     }
-    let shifts = num_chars;
-    let mask = !((!0u32) >> shifts << shifts);
+    let mask = u32::MAX >> (32 - num_chars);
     (gamma, (!gamma & mask))
 }
 
-fn parse_input(input: &[&str]) -> (usize, Vec<u32>) {
-    let num_chars = input[0].len();
-    assert!(input.iter().all(|line| line.len() == num_chars));
-    let ints: Vec<u32> = input
+fn parse_input(input: &[&str]) -> Result<(usize, Vec<u32>)> {
+    let num_chars = input.first().map_or(0, |line| line.len());
+    if !(1..=32).contains(&num_chars) {
+        return Err(Error::invalid("expected numbers of 1 to 32 bits").on_line(1));
+    }
+    let ints = input
         .iter()
-        .filter_map(|s| u32::from_str_radix(s, 2).ok())
-        .collect();
-    (num_chars, ints)
+        .enumerate()
+        .map(|(i, line)| {
+            if line.len() != num_chars {
+                return Err(Error::invalid(format!("expected {} bits", num_chars)).on_line(i + 1));
+            }
+            u32::from_str_radix(line, 2)
+                .map_err(|_| Error::parse("expected a binary number").on_line(i + 1))
+        })
+        .collect::<Result<_>>()?;
+    Ok((num_chars, ints))
 }
 
 fn get_bit_at(input: u32, n: usize) -> u32 {
@@ -121,7 +138,8 @@ mod tests {
         let expected_gamma = u32::from_str_radix("10110", 2).unwrap();
         let expected_epsilon = u32::from_str_radix("01001", 2).unwrap();
 
-        let (gamma, epsilon) = compute_gamma_epsilon(&input[..]);
+        let (num_chars, ints) = parse_input(&input[..]).unwrap();
+        let (gamma, epsilon) = compute_gamma_epsilon(num_chars, &ints);
         assert_eq!(gamma, expected_gamma);
         assert_eq!(epsilon, expected_epsilon);
     }
@@ -135,11 +153,29 @@ mod tests {
 
         let expected_oxygen_rating = 23;
         let expected_co2_scrubber_rating = 10;
-        let (num_chars, ints) = parse_input(&input[..]);
+        let (num_chars, ints) = parse_input(&input[..]).unwrap();
         assert_eq!(
-            expected_co2_scrubber_rating,
+            Ok(expected_co2_scrubber_rating),
             compute_co2_scrubber(&ints[..], num_chars)
         );
-        assert_eq!(expected_oxygen_rating, compute_oxygen(&ints[..], num_chars));
+        assert_eq!(
+            Ok(expected_oxygen_rating),
+            compute_oxygen(&ints[..], num_chars)
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            "invalid input at line 2: expected 3 bits",
+            Day3::parse(
+                "101
+1101
+"
+            )
+            .unwrap_err()
+            .to_string()
+        );
+        assert!(compute_oxygen(&[5, 5], 3).is_err());
     }
 }
@@ -1,31 +1,46 @@
-use crate::reader::{parse_ints, read_lines};
-
-pub fn main() {
-    let input: Vec<String> = read_lines("input/day4")
-        .unwrap()
-        .filter_map(|l| l.ok())
-        .filter(|l| l.len() > 0)
-        .collect();
-    let input: Vec<&str> = input.iter().map(AsRef::as_ref).collect();
-    let mut game = Game::new(input);
-    println!("Day 4-1 result: {}", game.run_game(true));
-    println!("Day 4-2 result: {}", game.run_game(false));
+use crate::error::{Error, Result};
+use crate::reader::parse_numbers;
+use crate::solution::Solution;
+
+/// Day 4: Giant Squid
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Game;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Game::new(input.lines().collect())
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        input.clone().run_game(true)
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        input.clone().run_game(false)
+    }
 }
 
+#[derive(Clone)]
 struct Field {
     pub marked: bool,
     pub value: u32,
 }
 
+#[derive(Clone)]
 struct Board {
     pub fields: Vec<Field>,
     pub finished: bool,
 }
 
 impl Board {
-    pub fn new(input: &[u32]) -> Self {
-        assert_eq!(input.len(), 25);
-        Self {
+    pub fn new(input: &[u32]) -> Result<Self> {
+        if input.len() != 25 {
+            return Err(Error::invalid("a board needs 5 rows of 5 numbers"));
+        }
+        Ok(Self {
             fields: input
                 .iter()
                 .map(|i| Field {
@@ -34,7 +49,7 @@ impl Board {
                 })
                 .collect(),
             finished: false,
-        }
+        })
     }
 
     fn row_iter(&self, row: u8) -> Box<dyn Iterator<Item = &Field> + '_> {
@@ -64,31 +79,45 @@ impl Board {
     }
 }
 
-struct Game {
-    pub numbers: Vec<u32>,
-    pub boards: Vec<Board>,
+#[derive(Clone)]
+pub struct Game {
+    numbers: Vec<u32>,
+    boards: Vec<Board>,
 }
 
 impl Game {
-    pub fn new(input: Vec<&str>) -> Self {
-        let numbers = parse_ints(input[0], ",");
-        let num_boards = ((input.len()) - 1) / 5;
-        let boards: Vec<Board> = (0..num_boards)
-            .map(|board| {
-                let all_board = input
-                    .iter()
-                    .skip((1 + board * 5) as usize)
-                    .take(5)
-                    .map(|i| String::from(*i))
-                    .collect::<Vec<String>>()
-                    .join(" ");
-                Board::new(&parse_ints(&all_board, " "))
-            })
+    pub fn new(input: Vec<&str>) -> Result<Self> {
+        //Keep the line numbers around for errors, empty lines only separate the boards
+        let lines: Vec<(usize, &str)> = input
+            .into_iter()
+            .enumerate()
+            .map(|(i, l)| (i + 1, l))
+            .filter(|(_, l)| !l.trim().is_empty())
             .collect();
-        Self { numbers, boards }
+        let (first, numbers) = lines.first().ok_or(Error::parse("empty input"))?;
+        let numbers = parse_numbers(numbers).map_err(|e| e.on_line(*first))?;
+        let board_lines = &lines[1..];
+        if !board_lines.len().is_multiple_of(5) {
+            return Err(Error::invalid("every board needs 5 rows"));
+        }
+        let boards = board_lines
+            .chunks(5)
+            .map(|rows| {
+                let mut values = Vec::new();
+                for (line, row) in rows {
+                    let row = parse_numbers::<u32>(row).map_err(|e| e.on_line(*line))?;
+                    if row.len() != 5 {
+                        return Err(Error::invalid("a board row needs 5 numbers").on_line(*line));
+                    }
+                    values.extend(row);
+                }
+                Board::new(&values)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { numbers, boards })
     }
 
-    pub fn run_game(&mut self, finish_first: bool) -> u32 {
+    pub fn run_game(&mut self, finish_first: bool) -> Result<u32> {
         for number in &self.numbers {
             let mut remaining = self.boards.iter().filter(|b| !b.is_finished()).count();
             for board in &mut self.boards {
@@ -101,17 +130,19 @@ impl Game {
                     remaining -= 1;
                 }
                 if (finish_first || remaining == 0) && finished {
-                    return board
+                    return Ok(board
                         .fields
                         .iter()
                         .filter(|f| !f.marked)
                         .map(|f| f.value)
                         .sum::<u32>()
-                        * number;
+                        * number);
                 }
             }
         }
-        panic!("Game didn't finish!");
+        Err(Error::no_solution(
+            "the numbers run out before the game finishes",
+        ))
     }
 }
 
@@ -129,7 +160,7 @@ mod tests {
             6, 10, 3, 18, 5,
             1, 12, 20, 15, 19,
         ];
-        let mut board = Board::new(&input);
+        let mut board = Board::new(&input).unwrap();
         assert_eq!(
             board.row_iter(0).map(|f| f.value).collect::<Vec<_>>(),
             vec![22u32, 13, 17, 11, 0]
@@ -144,15 +175,15 @@ mod tests {
         );
 
         board.run_number(17);
-        assert_eq!(false, board.is_finished());
+        assert!(!board.is_finished());
         board.run_number(23);
-        assert_eq!(false, board.is_finished());
+        assert!(!board.is_finished());
         board.run_number(14);
-        assert_eq!(false, board.is_finished());
+        assert!(!board.is_finished());
         board.run_number(3);
-        assert_eq!(false, board.is_finished());
+        assert!(!board.is_finished());
         board.run_number(20);
-        assert_eq!(false, board.is_finished());
+        assert!(board.is_finished());
     }
 
     #[test]
@@ -176,10 +207,43 @@ mod tests {
             "2  0 12  3  7",
         ];
 
-        let mut game = Game::new(input);
+        let mut game = Game::new(input).unwrap();
         assert_eq!(27, game.numbers.len());
         assert_eq!(3, game.boards.len());
-        assert_eq!(4512, game.run_game(true));
-        assert_eq!(1924, game.run_game(false));
+        assert_eq!(Ok(4512), game.run_game(true));
+        assert_eq!(Ok(1924), game.run_game(false));
+    }
+
+    #[test]
+    fn unfinished_game() {
+        let input = vec!["1,2", "", "1 2 3 4 5", "6 7 8 9 10"];
+        assert_eq!(
+            "invalid input: every board needs 5 rows",
+            Game::new(input).err().unwrap().to_string()
+        );
+        let input = vec![
+            "1,2",
+            "1 2 3 4 5",
+            "6 7 8 9 10",
+            "1 1 1 1 1",
+            "1 1 1 1 1",
+            "1 1 1 1",
+        ];
+        assert_eq!(
+            "invalid input at line 6: a board row needs 5 numbers",
+            Game::new(input).err().unwrap().to_string()
+        );
+        let input = vec![
+            "1,2",
+            "1 2 3 4 5",
+            "6 7 8 9 10",
+            "1 1 1 1 1",
+            "1 1 1 1 1",
+            "1 1 1 1 1",
+        ];
+        assert!(matches!(
+            Game::new(input).unwrap().run_game(true),
+            Err(Error::NoSolution(_))
+        ));
     }
 }
@@ -2,42 +2,49 @@ use std::collections::HashMap;
 use std::mem::swap;
 use std::ops::RangeInclusive;
 
-use crate::reader::read_lines;
+use nom::IResult;
 
-pub fn main() {
-    let mut world = make_world(false);
+use crate::error::{parse_all, parse_lines, Error, Result};
+use crate::parsers::{arrow, coordinates};
+use crate::solution::Solution;
 
-    println!("Day 5-1 result: {}", world.count_danger());
-    let mut world = make_world(true);
+/// Day 5: Hydrothermal Venture
+pub struct Day5;
 
-    println!("Day 5-2 result: {}", world.count_danger());
-}
+impl Solution for Day5 {
+    type Input = Vec<Segment>;
+    type PartOne = u32;
+    type PartTwo = u32;
 
-fn make_world(allow_diagonal: bool) -> World {
-    let input: Vec<String> = read_lines("input/day5")
-        .unwrap()
-        .filter_map(|l| l.ok())
-        .filter(|l| l.len() > 0)
-        .collect();
-    let input: Vec<&str> = input.iter().map(AsRef::as_ref).collect();
-    let lines: Vec<Line> = input
-        .iter()
-        .filter_map(|l| Line::from_str(l, allow_diagonal).ok())
-        .flatten()
-        .collect();
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_lines(input, |line| parse_all(line, segment))
+    }
 
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(make_world(input, false)?.count_danger())
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(make_world(input, true)?.count_danger())
+    }
+}
+
+fn make_world(input: &[Segment], allow_diagonal: bool) -> Result<World> {
     let mut world = World::new();
-    for line in lines {
-        world.add_line(&line);
+    for (i, segment) in input.iter().enumerate() {
+        for line in Line::from_segment(*segment, allow_diagonal).map_err(|e| e.on_line(i + 1))? {
+            world.add_line(&line);
+        }
     }
-    world
+    Ok(world)
 }
 
-#[derive(Debug)]
-enum Day5Err {
-    ParseError,
-    IsNotHorizontalOrVertical,
-    CantMakeDiagonal,
+type Point = (u32, u32);
+type Segment = (Point, Point);
+
+//"0,9 -> 5,9"
+fn segment(input: &str) -> IResult<&str, Segment> {
+    arrow(coordinates, coordinates)(input)
 }
 
 type MyRange = RangeInclusive<u32>;
@@ -68,25 +75,21 @@ impl World {
     pub fn add_line(&mut self, line: &Line) {
         if Self::is_horizontal(line) {
             //horizontal
-            let list = self
-                .x_lines
-                .entry(*line.y.start())
-                .or_insert_with(|| Vec::new());
+            let list = self.x_lines.entry(*line.y.start()).or_default();
             list.push(line.x.clone());
         } else {
-            let list = self
-                .y_lines
-                .entry(*line.x.start())
-                .or_insert_with(|| Vec::new());
+            let list = self.y_lines.entry(*line.x.start()).or_default();
             list.push(line.y.clone());
         }
     }
 
     pub fn count_danger(&mut self) -> u32 {
         let mut map = HashMap::<u32, Vec<u32>>::new();
-        const I: usize = 1000;
+        let x_ends = self.x_lines.values().flatten().map(|range| *range.end());
+        let width = x_ends.chain(self.y_lines.keys().copied()).max();
+        let width = width.map_or(0, |x| x as usize + 1);
         for (y, x_ranges) in self.x_lines.clone() {
-            let list = map.entry(y).or_insert_with(|| vec![0u32; I]);
+            let list = map.entry(y).or_insert_with(|| vec![0u32; width]);
             for x_range in x_ranges {
                 for x in x_range {
                     list[x as usize] += 1;
@@ -96,39 +99,35 @@ impl World {
         for (x, y_ranges) in self.y_lines.clone() {
             for y_range in y_ranges {
                 for y in y_range {
-                    let list = map.entry(y).or_insert_with(|| vec![0u32; I]);
+                    let list = map.entry(y).or_insert_with(|| vec![0u32; width]);
                     list[x as usize] += 1;
                 }
             }
         }
-        map.iter()
-            .map(|(_, line)| line.iter().filter(|v| **v >= 2).count() as u32)
+        map.values()
+            .map(|line| line.iter().filter(|v| **v >= 2).count() as u32)
             .sum::<u32>()
     }
 }
 
 impl Line {
-    //"0,9 -> 5,9"
-    fn from_str(s: &str, allow_diagonal: bool) -> Result<Vec<Self>, Day5Err> {
-        let coords: Vec<&str> = s.split(" ").collect();
-        if coords.len() != 3 {
-            return Err(Day5Err::ParseError);
-        }
-        fn parse_chunk(chunk: &str) -> Result<(u32, u32), Day5Err> {
-            let chunks: Vec<&str> = chunk.split(",").collect();
-            if chunks.len() != 2 {
-                return Err(Day5Err::ParseError);
-            }
-            Ok((
-                chunks[0].parse::<u32>().map_err(|_| Day5Err::ParseError)?,
-                chunks[1].parse::<u32>().map_err(|_| Day5Err::ParseError)?,
-            ))
-        }
-        let (mut xs, mut ys) = parse_chunk(coords[0])?;
-        let (mut xe, mut ye) = parse_chunk(coords[2])?;
+    #[cfg(test)]
+    fn from_str(s: &str, allow_diagonal: bool) -> Result<Vec<Self>> {
+        Self::from_segment(crate::error::parse_all(s, segment)?, allow_diagonal)
+    }
+
+    /// Diagonal segments are split up into single points, or skipped when not allowed.
+    fn from_segment(
+        ((mut xs, mut ys), (mut xe, mut ye)): Segment,
+        allow_diagonal: bool,
+    ) -> Result<Vec<Self>> {
         if xs != xe && ys != ye {
-            if !allow_diagonal {
-                Err(Day5Err::IsNotHorizontalOrVertical)
+            if xs.abs_diff(xe) != ys.abs_diff(ye) {
+                Err(Error::invalid(
+                    "lines must be horizontal, vertical or diagonal",
+                ))
+            } else if !allow_diagonal {
+                Ok(Vec::new())
             } else {
                 //For diagonal lines always swap both or none, messes up points otherwise
                 if xs > xe {
@@ -142,7 +141,7 @@ impl Line {
                 };
                 let mut result = Vec::<Self>::new();
                 for x in xs..=xe {
-                    let y = y_iter.next().ok_or(Day5Err::CantMakeDiagonal)?;
+                    let y = y_iter.next().unwrap();
                     result.push(Self {
                         x: RangeInclusive::new(x, x),
                         y: RangeInclusive::new(y, y),
@@ -168,6 +167,8 @@ impl Line {
 
 #[cfg(test)]
 mod tests {
+    use crate::examples::example;
+
     use super::*;
 
     #[test]
@@ -208,21 +209,9 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let input = vec![
-            "0,9 -> 5,9",
-            "8,0 -> 0,8",
-            "9,4 -> 3,4",
-            "2,2 -> 2,1",
-            "7,0 -> 7,4",
-            "6,4 -> 2,0",
-            "0,9 -> 2,9",
-            "3,4 -> 1,4",
-            "0,0 -> 8,8",
-            "5,5 -> 8,2",
-        ];
-
+        let input = example(5, "example");
         let lines: Vec<Line> = input
-            .iter()
+            .lines()
             .filter_map(|l| Line::from_str(l, false).ok())
             .flatten()
             .collect();
@@ -237,21 +226,9 @@ mod tests {
 
     #[test]
     fn part_two() {
-        let input = vec![
-            "0,9 -> 5,9",
-            "8,0 -> 0,8",
-            "9,4 -> 3,4",
-            "2,2 -> 2,1",
-            "7,0 -> 7,4",
-            "6,4 -> 2,0",
-            "0,9 -> 2,9",
-            "3,4 -> 1,4",
-            "0,0 -> 8,8",
-            "5,5 -> 8,2",
-        ];
-
+        let input = example(5, "example");
         let lines: Vec<Line> = input
-            .iter()
+            .lines()
             .filter_map(|l| Line::from_str(l, true).ok())
             .flatten()
             .collect();
@@ -1,24 +1,44 @@
-use crate::reader::parse_ints;
+use crate::error::{Error, Result};
+use crate::reader::parse_numbers;
+use crate::solution::Solution;
 
-pub fn main() {
-    let input = "5,1,1,3,1,1,5,1,2,1,5,2,5,1,1,1,4,1,1,5,1,1,4,1,1,1,3,5,1,1,1,1,1,1,1,1,1,4,4,4,1,1,1,1,1,4,1,1,1,1,1,5,1,1,1,4,1,1,1,1,1,3,1,1,4,1,4,1,1,2,3,1,1,1,1,4,1,2,2,1,1,1,1,1,1,3,1,1,1,1,1,2,1,1,1,1,1,1,1,4,4,1,4,2,1,1,1,1,1,4,3,1,1,1,1,2,1,1,1,2,1,1,3,1,1,1,2,1,1,1,3,1,3,1,1,1,1,1,1,1,1,1,3,1,1,1,1,3,1,1,1,1,1,1,2,1,1,2,3,1,2,1,1,4,1,1,5,3,1,1,1,2,4,1,1,2,4,2,1,1,1,1,1,1,1,2,1,1,1,1,1,1,1,1,4,3,1,2,1,2,1,5,1,2,1,1,5,1,1,1,1,1,1,2,2,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,4,1,1,1,1,1,3,1,1,5,1,1,1,1,5,1,4,1,1,1,4,1,3,4,1,4,1,1,1,1,1,1,1,1,1,3,5,1,3,1,1,1,1,4,1,5,3,1,1,1,1,1,5,1,1,1,2,2";
-    let input = parse_ints(input, ",");
-    println!("Day 6-1 {}", run(input, 80));
+/// Day 6: Lanternfish
+pub struct Day6;
 
-    let input = "5,1,1,3,1,1,5,1,2,1,5,2,5,1,1,1,4,1,1,5,1,1,4,1,1,1,3,5,1,1,1,1,1,1,1,1,1,4,4,4,1,1,1,1,1,4,1,1,1,1,1,5,1,1,1,4,1,1,1,1,1,3,1,1,4,1,4,1,1,2,3,1,1,1,1,4,1,2,2,1,1,1,1,1,1,3,1,1,1,1,1,2,1,1,1,1,1,1,1,4,4,1,4,2,1,1,1,1,1,4,3,1,1,1,1,2,1,1,1,2,1,1,3,1,1,1,2,1,1,1,3,1,3,1,1,1,1,1,1,1,1,1,3,1,1,1,1,3,1,1,1,1,1,1,2,1,1,2,3,1,2,1,1,4,1,1,5,3,1,1,1,2,4,1,1,2,4,2,1,1,1,1,1,1,1,2,1,1,1,1,1,1,1,1,4,3,1,2,1,2,1,5,1,2,1,1,5,1,1,1,1,1,1,2,2,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,4,1,1,1,1,1,3,1,1,5,1,1,1,1,5,1,4,1,1,1,4,1,3,4,1,4,1,1,1,1,1,1,1,1,1,3,5,1,3,1,1,1,1,4,1,5,3,1,1,1,1,1,5,1,1,1,2,2";
-    let input = parse_ints(input, ",");
-    println!("Day 6-2 {}", run(input, 256));
+impl Solution for Day6 {
+    type Input = Vec<u32>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let fishes = parse_numbers(input)?;
+        if let Some(i) = fishes.iter().position(|f| *f > 8) {
+            return Err(Error::invalid(format!(
+                "fish {} has a timer above 8",
+                i + 1
+            )));
+        }
+        Ok(fishes)
+    }
+
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(run(input, 80))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(run(input, 256))
+    }
 }
 
-fn run(fishes: Vec<u32>, days: u32) -> u64 {
+fn run(fishes: &[u32], days: u32) -> u64 {
     let mut histogram = [0u64; 9];
     for f in fishes {
-        histogram[f as usize] += 1u64;
+        histogram[*f as usize] += 1u64;
     }
 
     let mut t = 0u32;
     while t < days {
-        let prev_state = histogram.clone();
+        let prev_state = histogram;
 
         histogram[0] = prev_state[1];
         histogram[1] = prev_state[2];
@@ -41,15 +61,22 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let input = "3,4,3,1,2";
-        let input = parse_ints(input, ",");
+        let input = Day6::parse("3,4,3,1,2").unwrap();
 
-        assert_eq!(26, run(input, 18));
+        assert_eq!(26, run(&input, 18));
 
         let input = vec![3, 4, 3, 1, 2];
-        assert_eq!(5934, run(input, 80));
+        assert_eq!(5934, run(&input, 80));
 
         let input = vec![3, 4, 3, 1, 2];
-        assert_eq!(26984457539, run(input, 256));
+        assert_eq!(26984457539, run(&input, 256));
+    }
+
+    #[test]
+    fn invalid_timer() {
+        assert_eq!(
+            "invalid input: fish 2 has a timer above 8",
+            Day6::parse("3,9,1").unwrap_err().to_string()
+        );
     }
 }
@@ -1,26 +1,38 @@
-use crate::reader::read_lines_filter_ok;
+use nom::character::complete::{alpha1, char, space0, space1};
+use nom::multi::separated_list1;
+use nom::sequence::{delimited, separated_pair};
+use nom::IResult;
 
-pub fn main() {
-    let input = read_lines_filter_ok("input/day8")
-        .iter()
-        .filter_map(|s| parse_line(s).ok())
-        .collect();
-    println!("Day 8-1: {}", count_easies(input));
-    let input = read_lines_filter_ok("input/day8")
-        .iter()
-        .filter_map(|s| parse_line(s).ok())
-        .collect();
+use crate::error::{parse_all, parse_lines, Error, Result};
+use crate::solution::Solution;
 
-    println!("Day 8-2: {}", sum_full_parsed(input));
-}
+/// Day 8: Seven Segment Search
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<Entry>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        //Deduce while parsing, so wiring that can't be solved is reported on its own line
+        parse_lines(input, |line| {
+            let entry = parse_line(line)?;
+            deduce_digits(&entry)?;
+            Ok(entry)
+        })
+    }
 
-#[derive(Debug)]
-enum Day8Error {
-    ParseError,
-    DeductionFailed,
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne> {
+        Ok(count_easies(input))
+    }
+
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo> {
+        sum_full_parsed(input)
+    }
 }
 
-type Input = (Vec<String>, Vec<String>);
+type Entry = (Vec<String>, Vec<String>);
 type DeductionState = Vec<Option<String>>;
 
 fn is_easy(input: &str) -> bool {
@@ -33,18 +45,15 @@ fn is_easy(input: &str) -> bool {
     }
 }
 
-fn count_easies(inputs: Vec<Input>) -> u32 {
+fn count_easies(inputs: &[Entry]) -> u32 {
     inputs
         .iter()
         .map(|(_, values)| values.iter().filter(|v| is_easy(v.as_str())).count() as u32)
         .sum::<u32>()
 }
 
-fn sum_full_parsed(inputs: Vec<Input>) -> u32 {
-    inputs
-        .iter()
-        .filter_map(|input| deduce_digits(input).ok())
-        .sum::<u32>()
+fn sum_full_parsed(inputs: &[Entry]) -> Result<u32> {
+    inputs.iter().map(deduce_digits).sum()
 }
 
 fn parse_zero_six_nine(input: &str, state: &DeductionState) -> Option<u32> {
@@ -146,11 +155,11 @@ fn deduce(input: &str, state: &DeductionState) -> Option<u32> {
     }
 }
 
-fn deduce_digits((patterns, values): &Input) -> Result<u32, Day8Error> {
+fn deduce_digits((patterns, values): &Entry) -> Result<u32> {
     let mut state: DeductionState = vec![None; 10];
 
     let mut undeduced = patterns.clone();
-    while undeduced.len() > 0 {
+    while !undeduced.is_empty() {
         let count_before = undeduced.len();
         undeduced.retain(|pattern| {
             let deduction = deduce(pattern, &state);
@@ -164,88 +173,75 @@ fn deduce_digits((patterns, values): &Input) -> Result<u32, Day8Error> {
         });
         let count_after = undeduced.len();
         if count_before == count_after {
-            return Err(Day8Error::DeductionFailed);
+            return Err(Error::no_solution("could not deduce the wiring"));
         }
     }
     if state.iter().any(|v| v.is_none()) {
-        return Err(Day8Error::DeductionFailed);
+        return Err(Error::no_solution("could not deduce the wiring"));
     }
-    let state: Vec<_> = state.into_iter().filter_map(|f| f).collect();
+    let state: Vec<_> = state.into_iter().flatten().collect();
 
-    let digits: Vec<usize> = values
+    let digits = values
         .iter()
-        .map(|v| state.iter().position(|parsed| parsed == v).unwrap())
-        .collect();
+        .map(|v| {
+            state
+                .iter()
+                .position(|parsed| parsed == v)
+                .ok_or_else(|| Error::invalid(format!("{} is not one of the patterns", v)))
+        })
+        .collect::<Result<Vec<usize>>>()?;
 
     Ok((digits[0] * 1000 + digits[1] * 100 + digits[2] * 10 + digits[3]) as u32)
 }
 
-fn parse_line(line: &str) -> Result<Input, Day8Error> {
-    let patterns = line.split("|").next().ok_or(Day8Error::ParseError)?;
-    let values = line
-        .split("|")
-        .skip(1)
-        .next()
-        .ok_or(Day8Error::ParseError)?;
+fn words(input: &str) -> IResult<&str, Vec<&str>> {
+    separated_list1(space1, alpha1)(input)
+}
+
+//"be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe"
+fn parse_line(line: &str) -> Result<Entry> {
+    let separator = delimited(space0, char('|'), space0);
+    let (patterns, values) = parse_all(line.trim(), separated_pair(words, separator, words))?;
+    if patterns.len() != 10 || values.len() != 4 {
+        return Err(Error::parse("expected 10 patterns and 4 values"));
+    }
 
     fn sort(v: &str) -> String {
         let mut l: Vec<char> = v.chars().collect();
         l.sort_unstable();
         l.into_iter().collect()
     }
-    let patterns: Vec<String> = patterns
-        .split(" ")
-        .filter(|p| p.len() > 0)
-        .take(10)
-        .map(sort)
-        .collect();
-    let values: Vec<String> = values
-        .split(" ")
-        .filter(|p| p.len() > 0)
-        .take(4)
-        .map(sort)
-        .collect();
-    if patterns.len() != 10 || values.len() != 4 {
-        Err(Day8Error::ParseError)
-    } else {
-        Ok((patterns, values))
-    }
+    Ok((
+        patterns.into_iter().map(sort).collect(),
+        values.into_iter().map(sort).collect(),
+    ))
 }
 
 #[cfg(test)]
 mod test {
+    use crate::examples::example;
+
     use super::*;
 
-    fn test_input() -> Vec<&'static str> {
-        vec![
-            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
-            "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc",
-            "fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg",
-            "fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb",
-            "aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea",
-            "fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb",
-            "dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe",
-            "bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef",
-            "egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb",
-            "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce",
-        ]
+    fn test_input() -> Vec<String> {
+        example(8, "example").lines().map(String::from).collect()
     }
 
     #[test]
     fn part_one_parse() {
         assert!(test_input()
             .iter()
-            .map(|s| parse_line(&s))
+            .map(|s| parse_line(s))
             .all(|r| r.is_ok()));
     }
 
     #[test]
     fn part_one() {
-        let input: Vec<Input> = test_input()
+        let input: Vec<Entry> = test_input()
             .iter()
-            .filter_map(|p| parse_line(&p).ok())
+            .filter_map(|p| parse_line(p).ok())
             .collect();
-        assert_eq!(26, count_easies(input));
+        assert_eq!(26, count_easies(&input));
     }
 
     #[test]
@@ -259,9 +255,9 @@ mod test {
 
     #[test]
     fn part_two_all_parts() {
-        let input: Vec<Input> = test_input()
+        let input: Vec<Entry> = test_input()
             .iter()
-            .filter_map(|p| parse_line(&p).ok())
+            .filter_map(|p| parse_line(p).ok())
             .collect();
         assert_eq!(8394, deduce_digits(&input[0]).unwrap());
         assert_eq!(9781, deduce_digits(&input[1]).unwrap());
@@ -277,10 +273,21 @@ mod test {
 
     #[test]
     fn part_two() {
-        let input: Vec<Input> = test_input()
+        let input: Vec<Entry> = test_input()
             .iter()
-            .filter_map(|p| parse_line(&p).ok())
+            .filter_map(|p| parse_line(p).ok())
             .collect();
-        assert_eq!(61229, sum_full_parsed(input));
+        assert_eq!(Ok(61229), sum_full_parsed(&input));
+    }
+
+    #[test]
+    fn deduction_error_line() {
+        let unknown_value =
+            "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb bc";
+        let input = format!("\n{}\n", unknown_value);
+        assert_eq!(
+            "invalid input at line 2: bc is not one of the patterns",
+            Day8::parse(&input).unwrap_err().to_string()
+        );
     }
 }
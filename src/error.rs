@@ -0,0 +1,248 @@
+//! The error every parser and solver returns.
+
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+use nom::IResult;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A 1-based position in the puzzle input, `0` where it is not known.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(line: usize, column: usize) -> Self {
+        Location { line, column }
+    }
+
+    /// Location of `rest`, a suffix of `input` as left over by a parser.
+    pub fn of(input: &str, rest: &str) -> Self {
+        let offset = input.len().saturating_sub(rest.len());
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Location {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (0, _) => Ok(()),
+            (line, 0) => write!(f, " at line {}", line),
+            (line, column) => write!(f, " at line {}, column {}", line, column),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input does not have the expected format.
+    Parse { location: Location, message: String },
+    /// The input is well formed, but breaks an assumption of the puzzle.
+    Invalid { location: Location, message: String },
+    /// The input is valid, but has no answer.
+    NoSolution(String),
+    /// The input could not be read at all.
+    Io(String),
+}
+
+impl Error {
+    pub fn parse(message: impl Into<String>) -> Self {
+        Error::Parse {
+            location: Location::default(),
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Error::Invalid {
+            location: Location::default(),
+            message: message.into(),
+        }
+    }
+
+    pub fn no_solution(message: impl Into<String>) -> Self {
+        Error::NoSolution(message.into())
+    }
+
+    /// Converts a failed nom parse of `input`, pointing at where the parser gave up.
+    pub fn nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => {
+                Error::parse("unexpected end of input").at(Location::of(input, ""))
+            }
+            nom::Err::Error(e) | nom::Err::Failure(e) => Error::parse(format!(
+                "{} ({})",
+                unexpected(e.input),
+                e.code.description()
+            ))
+            .at(Location::of(input, e.input)),
+        }
+    }
+
+    /// Sets the location, unless one is known already.
+    pub fn at(self, at: Location) -> Self {
+        match self {
+            Error::Parse { location, message } if location.line == 0 => Error::Parse {
+                location: at,
+                message,
+            },
+            Error::Invalid { location, message } if location.line == 0 => Error::Invalid {
+                location: at,
+                message,
+            },
+            e => e,
+        }
+    }
+
+    /// Places an error found while handling a single line on line `line` of the input.
+    pub fn on_line(self, line: usize) -> Self {
+        match &self {
+            Error::Parse { location, .. } | Error::Invalid { location, .. } => {
+                let column = location.column;
+                let line = line + location.line.saturating_sub(1);
+                self.at_unchecked(Location { line, column })
+            }
+            Error::NoSolution(_) | Error::Io(_) => self,
+        }
+    }
+
+    fn at_unchecked(self, location: Location) -> Self {
+        match self {
+            Error::Parse { message, .. } => Error::Parse { location, message },
+            Error::Invalid { message, .. } => Error::Invalid { location, message },
+            e => e,
+        }
+    }
+}
+
+fn unexpected(rest: &str) -> String {
+    match rest.lines().next() {
+        None | Some("") => "unexpected end of line".to_string(),
+        Some(line) if line.chars().count() > 20 => {
+            format!(
+                "unexpected \"{}...\"",
+                line.chars().take(20).collect::<String>()
+            )
+        }
+        Some(line) => format!("unexpected \"{}\"", line),
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { location, message } => {
+                write!(f, "parse error{}: {}", location, message)
+            }
+            Error::Invalid { location, message } => {
+                write!(f, "invalid input{}: {}", location, message)
+            }
+            Error::NoSolution(message) => write!(f, "no solution: {}", message),
+            Error::Io(message) => write!(f, "could not read input: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Self {
+        Error::parse(format!("invalid number: {}", e))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e.to_string())
+    }
+}
+
+/// Runs a nom parser over all of `input`, allowing only trailing whitespace to remain.
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&'a str) -> IResult<&'a str, T>,
+) -> Result<T> {
+    let (rest, parsed) = parser(input).map_err(|e| Error::nom(input, e))?;
+    if rest.trim().is_empty() {
+        Ok(parsed)
+    } else {
+        Err(Error::parse(unexpected(rest)).at(Location::of(input, rest)))
+    }
+}
+
+/// Parses every non-empty line, attaching the line number to errors.
+pub fn parse_lines<T>(input: &str, parse: impl Fn(&str) -> Result<T>) -> Result<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse(line).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use nom::bytes::complete::tag;
+    use nom::character::complete::u32;
+    use nom::sequence::preceded;
+
+    use super::*;
+
+    #[test]
+    fn location() {
+        let input = "abc\ndef\nghi";
+        assert_eq!(Location::new(1, 1), Location::of(input, input));
+        assert_eq!(Location::new(2, 2), Location::of(input, "ef\nghi"));
+        assert_eq!(Location::new(3, 4), Location::of(input, ""));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "parse error at line 2, column 3: bad",
+            Error::parse("bad").at(Location::new(2, 3)).to_string()
+        );
+        assert_eq!(
+            "invalid input at line 4: odd",
+            Error::invalid("odd").on_line(4).to_string()
+        );
+        assert_eq!("invalid input: odd", Error::invalid("odd").to_string());
+        assert_eq!("no solution: none", Error::no_solution("none").to_string());
+    }
+
+    #[test]
+    fn nom_errors() {
+        let parse = |input| parse_all(input, preceded(tag("x="), u32));
+        assert_eq!(Ok(12), parse("x=12\n"));
+        assert_eq!(
+            "parse error at line 1, column 3: unexpected \"a\" (Digit)",
+            parse("x=a").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "parse error at line 1, column 5: unexpected \" 3\"",
+            parse("x=12 3").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn lines() {
+        let parse = |line: &str| {
+            line.parse::<u32>()
+                .map_err(|e| Error::parse(e.to_string()).at(Location::new(1, 1)))
+        };
+        assert_eq!(Ok(vec![1, 2]), parse_lines("1\n\n2\n", parse));
+        assert_eq!(
+            "parse error at line 3, column 1: invalid digit found in string",
+            parse_lines("1\n\nx\n", parse).unwrap_err().to_string()
+        );
+    }
+}
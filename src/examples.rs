@@ -0,0 +1,111 @@
+//! The examples from the puzzle descriptions, kept as data rather than as test literals.
+//!
+//! `examples/dayN/<name>.txt` holds the input of an example and `examples/dayN/<name>.toml`
+//! the answers the puzzle gives for it, in the format of [`Answers`]. Either part may be left
+//! out, as many examples only come with an answer for one of them, but the answers must be
+//! under the `[dayN]` of the example's own directory. Examples without a `.toml` are data for
+//! the tests of a day, and are not run.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::answers::{Answers, Verdict};
+use crate::error::{Error, Result};
+use crate::runner;
+
+pub const EXAMPLES_DIR: &str = "examples";
+
+#[derive(Debug)]
+pub struct Example {
+    pub day: u32,
+    pub name: String,
+    pub input: String,
+    pub answers: Answers,
+}
+
+fn day_dir(dir: &Path, day: u32) -> PathBuf {
+    dir.join(format!("day{}", day))
+}
+
+impl Example {
+    /// Loads example `name` of `day` from `dir`, without answers if it has no `.toml`.
+    pub fn load(dir: &Path, day: u32, name: &str) -> Result<Example> {
+        let path = day_dir(dir, day).join(name);
+        let input = fs::read_to_string(path.with_extension("txt"))?;
+        let toml = path.with_extension("toml");
+        let answers = match fs::read_to_string(&toml) {
+            Ok(text) => {
+                let answers = Answers::parse(&text)
+                    .map_err(|e| Error::parse(format!("{}: {}", toml.display(), e)))?;
+                //Answers for any other day would never be checked
+                if answers.days().next().is_none() || answers.days().any(|d| d != day) {
+                    let message =
+                        format!("{}: expected answers under [day{}]", toml.display(), day);
+                    return Err(Error::invalid(message));
+                }
+                answers
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Answers::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Example {
+            day,
+            name: name.to_string(),
+            input,
+            answers,
+        })
+    }
+
+    /// Runs the example through its day, for every part it has an answer for.
+    pub fn check(&self) -> Result<Vec<(u8, Verdict)>> {
+        if self.answers == Answers::default() {
+            return Ok(Vec::new());
+        }
+        let day = runner::select(&(self.day..=self.day))
+            .pop()
+            .ok_or_else(|| Error::invalid(format!("there is no day {}", self.day)))?;
+        let parsed = day.runner.parse(&self.input)?;
+        let mut verdicts = Vec::new();
+        if self.answers.get(self.day, 1).is_some() {
+            let answer = parsed.part_one()?;
+            verdicts.push((1, self.answers.verify(self.day, 1, &answer)));
+        }
+        if self.answers.get(self.day, 2).is_some() {
+            let answer = parsed.part_two()?;
+            verdicts.push((2, self.answers.verify(self.day, 2, &answer)));
+        }
+        Ok(verdicts)
+    }
+}
+
+/// All examples of `day` in `dir`, ordered by name, or none if the day has no directory.
+pub fn examples(dir: &Path, day: u32) -> Result<Vec<Example>> {
+    let entries = match fs::read_dir(day_dir(dir, day)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names.sort();
+    names
+        .iter()
+        .map(|name| Example::load(dir, day, name))
+        .collect()
+}
+
+/// The input of example `name` of `day`, for tests of the steps in between.
+#[cfg(test)]
+pub fn example(day: u32, name: &str) -> String {
+    Example::load(Path::new(EXAMPLES_DIR), day, name)
+        .unwrap()
+        .input
+}
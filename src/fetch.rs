@@ -0,0 +1,307 @@
+//! Downloading a day's puzzle input into the input directory.
+//!
+//! The download goes through a [`Transport`], so tests can point [`fetch`] at a local server
+//! or an in-memory stub instead of adventofcode.com.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::info;
+
+/// The session cookie of a logged in adventofcode.com account, needed to fetch inputs.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Overrides [`BASE_URL`], e.g. with a local `http://` server.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const BASE_URL: &str = "https://adventofcode.com/2021";
+const USER_AGENT: &str = "aoc_2021 input fetcher (github.com/tduindam/aoc2021)";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    NoSuchDay(u32),
+    MissingSession,
+    /// The session holds characters a cookie value can't, like a line break.
+    InvalidSession,
+    /// The input was downloaded before, and is not fetched again.
+    Cached(PathBuf),
+    /// The server answered, but not with the input.
+    Status(u16),
+    /// The request could not be made or the response could not be read.
+    Transport(String),
+    Io(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NoSuchDay(day) => write!(f, "there is no day {}", day),
+            FetchError::MissingSession => write!(f, "{} is not set", SESSION_VAR),
+            FetchError::InvalidSession => {
+                write!(f, "{} is not a valid session cookie", SESSION_VAR)
+            }
+            FetchError::Cached(path) => write!(f, "{} already exists", path.display()),
+            FetchError::Status(400) => write!(f, "the server rejected the session (400)"),
+            FetchError::Status(404) => write!(f, "the input is not available (404)"),
+            FetchError::Status(status) => write!(f, "the server answered with status {}", status),
+            FetchError::Transport(message) => write!(f, "request failed: {}", message),
+            FetchError::Io(message) => write!(f, "could not save input: {}", message),
+        }
+    }
+}
+
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        FetchError::Io(e.to_string())
+    }
+}
+
+/// Makes the GET requests for [`fetch`].
+pub trait Transport {
+    /// The body of `url`, requested with `session` as the session cookie.
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError>;
+}
+
+/// Plain HTTP over a [`TcpStream`], only for `http://` urls like a local server.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Http;
+
+impl Transport for Http {
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| FetchError::Transport(format!("{} is not an http:// url", url)))?;
+        let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        let path = if path.is_empty() { "/" } else { path };
+        let transport = |e: io::Error| FetchError::Transport(e.to_string());
+
+        let mut stream = TcpStream::connect(host).map_err(transport)?;
+        //HTTP/1.0 keeps the server from answering in chunks
+        write!(
+            stream,
+            "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: {}\r\nCookie: session={}\r\n\r\n",
+            path, host, USER_AGENT, session
+        )
+        .map_err(transport)?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(transport)?;
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| FetchError::Transport("incomplete response".to_string()))?;
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .ok_or_else(|| FetchError::Transport("invalid status line".to_string()))?;
+        match status {
+            200 => Ok(body.to_string()),
+            status => Err(FetchError::Status(status)),
+        }
+    }
+}
+
+/// HTTPS through the `curl` executable, as std has no TLS.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Curl;
+
+impl Transport for Curl {
+    fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+        let transport = |e: io::Error| FetchError::Transport(format!("could not run curl: {}", e));
+        //The cookie goes in through stdin, to keep it out of the process list. Redirects are
+        //not followed, curl would send the custom header on to whatever host they point at.
+        let mut curl = Command::new("curl")
+            .args(["--silent", "--show-error", "--header", "@-"])
+            .args(["--user-agent", USER_AGENT, "--write-out", "\n%{http_code}"])
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(transport)?;
+        if let Some(mut stdin) = curl.stdin.take() {
+            writeln!(stdin, "Cookie: session={}", session).map_err(transport)?;
+        }
+        let output = curl.wait_with_output().map_err(transport)?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr);
+            return Err(FetchError::Transport(message.trim().to_string()));
+        }
+
+        let response = String::from_utf8(output.stdout)
+            .map_err(|_| FetchError::Transport("the response is not UTF-8".to_string()))?;
+        let (body, status) = response
+            .rsplit_once('\n')
+            .ok_or_else(|| FetchError::Transport("incomplete response".to_string()))?;
+        match status.parse() {
+            Ok(200) => Ok(body.to_string()),
+            Ok(status) => Err(FetchError::Status(status)),
+            Err(_) => Err(FetchError::Transport(format!(
+                "invalid status {:?}",
+                status
+            ))),
+        }
+    }
+}
+
+/// The transport able to reach `base_url`.
+pub fn transport_for(base_url: &str) -> Box<dyn Transport> {
+    if base_url.starts_with("http://") {
+        Box::new(Http)
+    } else {
+        Box::new(Curl)
+    }
+}
+
+/// Downloads the input of `day` from `base_url` into `<dir>/dayN`, returning the path.
+///
+/// An input that is already there is never downloaded again, the puzzle inputs don't change
+/// and the site asks not to be hammered.
+pub fn fetch(
+    transport: &dyn Transport,
+    base_url: &str,
+    session: &str,
+    day: u32,
+    dir: &Path,
+) -> Result<PathBuf, FetchError> {
+    if !(1..=25).contains(&day) {
+        return Err(FetchError::NoSuchDay(day));
+    }
+    let session = session.trim();
+    if session.is_empty() {
+        return Err(FetchError::MissingSession);
+    }
+    //It ends up verbatim in a Cookie header, where anything else could start a new header
+    if !session.chars().all(is_cookie_char) {
+        return Err(FetchError::InvalidSession);
+    }
+    let path = dir.join(format!("day{}", day));
+    if path.exists() {
+        return Err(FetchError::Cached(path));
+    }
+
+    let url = format!("{}/day/{}/input", base_url.trim_end_matches('/'), day);
+    info!("fetching day {} from {}", day, url);
+    let input = transport.get(&url, session)?;
+    fs::create_dir_all(dir)?;
+    //Write next to the input first, so a failed write leaves no half an input to be cached
+    let partial = dir.join(format!("day{}.part", day));
+    fs::write(&partial, input)?;
+    fs::rename(&partial, &path)?;
+    Ok(path)
+}
+
+/// The characters RFC 6265 allows in a cookie value: visible ASCII, except `"`, `,`, `;`
+/// and `\`.
+fn is_cookie_char(c: char) -> bool {
+    c.is_ascii_graphic() && !matches!(c, '"' | ',' | ';' | '\\')
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::env;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    struct Stub(RefCell<Vec<String>>);
+
+    impl Transport for Stub {
+        fn get(&self, url: &str, session: &str) -> Result<String, FetchError> {
+            self.0.borrow_mut().push(format!("{} {}", session, url));
+            Ok("3,4,3,1,2\n".to_string())
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc_2021_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    //Answers `count` requests with `status`, and hands back the request lines and cookies
+    fn serve(status: &'static str, count: usize) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/2021", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut seen = Vec::new();
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let request: Vec<String> = BufReader::new(&stream)
+                    .lines()
+                    .map(|line| line.unwrap())
+                    .take_while(|line| !line.is_empty())
+                    .filter(|line| line.starts_with("GET") || line.starts_with("Cookie"))
+                    .collect();
+                seen.extend(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: 6\r\n\r\n1\n2\n3\n",
+                    status
+                )
+                .unwrap();
+            }
+            seen
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn cached() {
+        let dir = scratch_dir("cached");
+        let stub = Stub(RefCell::new(Vec::new()));
+        let path = fetch(&stub, "http://stub/2021/", "abc\n", 6, &dir).unwrap();
+        assert_eq!(dir.join("day6"), path);
+        assert_eq!("3,4,3,1,2\n", fs::read_to_string(&path).unwrap());
+        assert_eq!(vec!["abc http://stub/2021/day/6/input"], *stub.0.borrow());
+
+        assert_eq!(
+            Err(FetchError::Cached(path)),
+            fetch(&stub, "http://stub/2021", "abc", 6, &dir)
+        );
+        assert_eq!(1, stub.0.borrow().len());
+        assert_eq!(
+            Err(FetchError::NoSuchDay(26)),
+            fetch(&stub, "http://stub/2021", "abc", 26, &dir)
+        );
+        assert_eq!(
+            Err(FetchError::MissingSession),
+            fetch(&stub, "http://stub/2021", " ", 7, &dir)
+        );
+        for session in ["abc\r\nX-Injected: 1", "abc def", "abc;path=/"] {
+            assert_eq!(
+                Err(FetchError::InvalidSession),
+                fetch(&stub, "http://stub/2021", session, 7, &dir)
+            );
+        }
+        assert_eq!(1, stub.0.borrow().len());
+        assert!(!dir.join("day7").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn local_server() {
+        let dir = scratch_dir("local_server");
+        let (url, server) = serve("200 OK", 1);
+        let path = fetch(transport_for(&url).as_ref(), &url, "abc", 9, &dir).unwrap();
+        assert_eq!("1\n2\n3\n", fs::read_to_string(path).unwrap());
+        assert_eq!(
+            vec!["GET /2021/day/9/input HTTP/1.0", "Cookie: session=abc"],
+            server.join().unwrap()
+        );
+
+        let (url, server) = serve("404 Not Found", 1);
+        assert_eq!(
+            Err(FetchError::Status(404)),
+            fetch(&Http, &url, "abc", 10, &dir)
+        );
+        server.join().unwrap();
+        assert!(!dir.join("day10").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
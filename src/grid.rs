@@ -0,0 +1,459 @@
+//! A rectangular grid of cells, stored row by row, and [`Plane`] to place one on a torus or
+//! the infinite plane.
+
+use std::fmt;
+use std::ops::{Add, Index, IndexMut, Sub};
+
+use crate::error::{Error, Result};
+use crate::neighbors::{self, Bounds, Neighbors, Topology};
+
+pub use crate::neighbors::Neighborhood;
+
+/// A cell position: `x` is the column and `y` the row, counted from the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pos {
+    pub fn new(x: usize, y: usize) -> Self {
+        Pos { x, y }
+    }
+
+    /// The position `offset` away, unless that would be left of or above the origin.
+    pub fn offset(self, (dx, dy): (i8, i8)) -> Option<Pos> {
+        Some(Pos {
+            x: self.x.checked_add_signed(dx as isize)?,
+            y: self.y.checked_add_signed(dy as isize)?,
+        })
+    }
+}
+
+/// A point in 3D space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point {
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Point { x, y, z }
+    }
+
+    pub fn manhattan_dist(&self, other: &Self) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid of `cells`, given row by row.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        if width * height != cells.len() {
+            return Err(Error::invalid(format!(
+                "a {}x{} grid needs {} cells, not {}",
+                width,
+                height,
+                width * height,
+                cells.len()
+            )));
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// A `width` by `height` grid with every cell set by `f`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..width * height)
+            .map(|i| f(Pos::new(i % width, i / width)))
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    /// Where `pos` is stored in [`Grid::cells`], if it lies within the grid.
+    pub fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos).then(|| pos.x + pos.y * self.width)
+    }
+
+    /// The position of the `index`th cell of [`Grid::cells`].
+    pub fn pos_of(&self, index: usize) -> Pos {
+        Pos::new(index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// All cells, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Takes the cells out, row by row.
+    pub fn into_cells(self) -> Vec<T> {
+        self.cells
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| Pos::new(i % width, i / width))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(self.width as u32, self.height as u32)
+    }
+
+    /// The neighbors of `pos` that lie within the grid.
+    pub fn neighbors(&self, pos: Pos, neighborhood: Neighborhood) -> impl Iterator<Item = Pos> {
+        neighbors::neighbors((pos.x as i32, pos.y as i32), self.bounds(), neighborhood)
+            .map(|(x, y)| Pos::new(x as usize, y as usize))
+    }
+
+    /// A grid of the same shape, with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Like [`Grid::map`], also handing `f` the position of the cell.
+    pub fn map_with_pos<U>(&self, mut f: impl FnMut(Pos, &T) -> U) -> Grid<U> {
+        Grid::from_fn(self.width, self.height, |pos| f(pos, &self[pos]))
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The grid mirrored along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |pos| {
+            self[Pos::new(pos.y, pos.x)].clone()
+        })
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    /// Panics when `pos` lies outside the grid, use [`Grid::get`] when it may.
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} lies outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} lies outside the grid", pos))
+    }
+}
+
+/// One line per row, with the cells written next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What lies beyond the edges of the grid of a [`Plane`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edges<T> {
+    /// Nothing, the grid is all there is.
+    Bounded,
+    /// The grid again, as if it were wrapped around a torus.
+    Wrapping,
+    /// The same value everywhere beyond the grid, which is a window on the infinite plane.
+    Infinite(T),
+}
+
+/// A [`Grid`] placed in a [`Topology`], so positions may lie beyond its edges.
+///
+/// Positions on the plane are `(x, y)` pairs that may be negative. The top left cell of the
+/// grid sits at [`Plane::origin`], which only moves when an infinite plane [grows](Plane::grow).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plane<T> {
+    grid: Grid<T>,
+    origin: (i32, i32),
+    edges: Edges<T>,
+}
+
+impl<T> Plane<T> {
+    pub fn new(grid: Grid<T>, edges: Edges<T>) -> Self {
+        Plane {
+            grid,
+            origin: (0, 0),
+            edges,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn grid_mut(&mut self) -> &mut Grid<T> {
+        &mut self.grid
+    }
+
+    pub fn edges(&self) -> &Edges<T> {
+        &self.edges
+    }
+
+    pub fn origin(&self) -> (i32, i32) {
+        self.origin
+    }
+
+    pub fn topology(&self) -> Topology {
+        match self.edges {
+            Edges::Bounded => Topology::Bounded(self.grid.bounds()),
+            Edges::Wrapping => Topology::Torus(self.grid.bounds()),
+            Edges::Infinite(_) => Topology::Infinite,
+        }
+    }
+
+    /// The cell of the grid at `pos`, after wrapping on a torus. `None` beyond the edges.
+    pub fn cell_pos(&self, (x, y): (i32, i32)) -> Option<Pos> {
+        let (x, y) = match self.topology() {
+            Topology::Torus(bounds) if !self.grid.is_empty() => (
+                x.rem_euclid(bounds.width as i32),
+                y.rem_euclid(bounds.height as i32),
+            ),
+            _ => (x - self.origin.0, y - self.origin.1),
+        };
+        let pos = Pos::new(usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        self.grid.contains(pos).then_some(pos)
+    }
+
+    /// Where the cell at `pos` of the grid sits on the plane.
+    pub fn plane_pos(&self, pos: Pos) -> (i32, i32) {
+        (pos.x as i32 + self.origin.0, pos.y as i32 + self.origin.1)
+    }
+
+    /// The positions of all cells of the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.grid.positions().map(|pos| self.plane_pos(pos))
+    }
+
+    /// The value at `pos`, which is `None` only beyond the edges of a bounded plane.
+    pub fn get(&self, pos: (i32, i32)) -> Option<&T> {
+        match (self.cell_pos(pos), &self.edges) {
+            (Some(cell), _) => Some(&self.grid[cell]),
+            (None, Edges::Infinite(outside)) => Some(outside),
+            (None, _) => None,
+        }
+    }
+
+    pub fn neighbors(&self, pos: (i32, i32), neighborhood: Neighborhood) -> Neighbors {
+        neighbors::neighbors(pos, self.topology(), neighborhood)
+    }
+
+    /// The next generation of a cellular simulation: the same grid positions, each set by
+    /// `f`, and `edges` beyond them.
+    pub fn next<U>(&self, edges: Edges<U>, mut f: impl FnMut((i32, i32)) -> U) -> Plane<U> {
+        Plane {
+            grid: self.grid.map_with_pos(|pos, _| f(self.plane_pos(pos))),
+            origin: self.origin,
+            edges,
+        }
+    }
+}
+
+impl<T: Clone> Plane<T> {
+    /// Widens the grid of an infinite plane by `by` cells on every side, filled with the value
+    /// beyond it. Bounded and wrapping planes are returned as they are.
+    pub fn grow(&self, by: usize) -> Plane<T> {
+        let outside = match &self.edges {
+            Edges::Infinite(outside) => outside,
+            _ => return self.clone(),
+        };
+        let grid = Grid::from_fn(
+            self.grid.width + 2 * by,
+            self.grid.height + 2 * by,
+            |pos| match (pos.x.checked_sub(by), pos.y.checked_sub(by)) {
+                (Some(x), Some(y)) => self.grid.get(Pos::new(x, y)).unwrap_or(outside).clone(),
+                _ => outside.clone(),
+            },
+        );
+        Plane {
+            grid,
+            origin: (self.origin.0 - by as i32, self.origin.1 - by as i32),
+            edges: self.edges.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid() -> Grid<u32> {
+        Grid::new(3, 2, vec![1, 2, 3, 4, 5, 6]).unwrap()
+    }
+
+    #[test]
+    fn access() {
+        let grid = grid();
+        assert_eq!(Some(&6), grid.get(Pos::new(2, 1)));
+        assert_eq!(None, grid.get(Pos::new(3, 0)));
+        assert_eq!(None, grid.get(Pos::new(0, 2)));
+        assert_eq!(4, grid[Pos::new(0, 1)]);
+        assert_eq!(Some(5), grid.index_of(Pos::new(2, 1)));
+        assert_eq!(Pos::new(1, 1), grid.pos_of(4));
+        assert_eq!(&[4, 5, 6], grid.row(1));
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(2, grid.rows().count());
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = grid();
+        let around = |pos, n| grid.neighbors(pos, n).collect::<Vec<_>>();
+        assert_eq!(
+            vec![Pos::new(1, 0), Pos::new(0, 1)],
+            around(Pos::new(0, 0), Neighborhood::Four)
+        );
+        assert_eq!(
+            vec![
+                Pos::new(0, 0),
+                Pos::new(1, 0),
+                Pos::new(0, 1),
+                Pos::new(1, 1)
+            ],
+            around(Pos::new(0, 0), Neighborhood::EightWithSelf)
+        );
+        assert_eq!(5, around(Pos::new(1, 1), Neighborhood::Eight).len());
+        assert_eq!(4, around(Pos::new(1, 1), Neighborhood::FourWithSelf).len());
+    }
+
+    #[test]
+    fn transform() {
+        let grid = grid();
+        assert_eq!("123\n456", grid.to_string());
+        assert_eq!("14\n25\n36", grid.transpose().to_string());
+        assert_eq!("246\n81012", grid.map(|c| c * 2).to_string());
+        assert_eq!(
+            "011\n122",
+            grid.map_with_pos(|pos, _| pos.x.min(1) + pos.y).to_string()
+        );
+        assert_eq!(
+            grid,
+            Grid::from_fn(3, 2, |pos| (pos.x + pos.y * 3 + 1) as u32)
+        );
+    }
+
+    #[test]
+    fn planes() {
+        let bounded = Plane::new(grid(), Edges::Bounded);
+        assert_eq!(None, bounded.get((-1, 0)));
+        assert_eq!(Some(&6), bounded.get((2, 1)));
+        assert_eq!(3, bounded.neighbors((0, 0), Neighborhood::Eight).count());
+
+        let torus = Plane::new(grid(), Edges::Wrapping);
+        assert_eq!(Some(&3), torus.get((-1, 0)));
+        assert_eq!(Some(&4), torus.get((3, -1)));
+        assert_eq!(Some(Pos::new(1, 1)), torus.cell_pos((7, 5)));
+
+        let infinite = Plane::new(grid(), Edges::Infinite(0));
+        assert_eq!(Some(&0), infinite.get((-1, 0)));
+        assert_eq!(None, infinite.cell_pos((3, 0)));
+        let grown = infinite.grow(1);
+        assert_eq!((-1, -1), grown.origin());
+        assert_eq!("00000\n01230\n04560\n00000", grown.grid().to_string());
+        assert_eq!(Some(&5), grown.get((1, 1)));
+        assert_eq!(bounded, bounded.grow(2));
+
+        //Count the cells around every cell, the value beyond an infinite plane included
+        let next = grown.next(Edges::Infinite(0), |pos| {
+            grown
+                .neighbors(pos, Neighborhood::Eight)
+                .filter(|n| grown.get(*n) != Some(&0))
+                .count()
+        });
+        assert_eq!("12321\n23532\n23532\n12321", next.grid().to_string());
+        assert_eq!((-1, -1), next.origin());
+    }
+}
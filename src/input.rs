@@ -0,0 +1,102 @@
+//! Locating and reading a day's puzzle input.
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use crate::info;
+
+/// Overrides the directory the `dayN` input files are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+const DEFAULT_INPUT_DIR: &str = "input";
+
+/// The directory holding the `dayN` input files, unless overridden by [`INPUT_DIR_VAR`].
+pub fn input_dir(dir: Option<OsString>) -> PathBuf {
+    dir.map_or_else(|| DEFAULT_INPUT_DIR.into(), PathBuf::from)
+}
+
+/// Where the runner gets a day's puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// One file per day, `<dir>/dayN`.
+    Dir(PathBuf),
+    /// A single file, only meaningful when running one day.
+    File(PathBuf),
+    /// Standard input, only meaningful when running one day.
+    Stdin,
+}
+
+impl InputSource {
+    /// Resolves `--input <file|->`, falling back to the (optionally overridden) input directory.
+    pub fn new(input: Option<&str>, dir: Option<OsString>) -> Self {
+        match input {
+            Some("-") => InputSource::Stdin,
+            Some(file) => InputSource::File(file.into()),
+            None => InputSource::Dir(input_dir(dir)),
+        }
+    }
+
+    pub fn is_single(&self) -> bool {
+        !matches!(self, InputSource::Dir(_))
+    }
+
+    pub fn read(&self, day: u32) -> io::Result<String> {
+        match self {
+            InputSource::Dir(dir) => {
+                let path = dir.join(format!("day{}", day));
+                info!("reading day {} from {}", day, path.display());
+                fs::read_to_string(path)
+            }
+            InputSource::File(file) => {
+                info!("reading day {} from {}", day, file.display());
+                fs::read_to_string(file)
+            }
+            InputSource::Stdin => {
+                info!("reading day {} from stdin", day);
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+/// The checked in input of `day`, for tests against the real puzzle.
+#[cfg(test)]
+pub fn real_input(day: u32) -> String {
+    InputSource::Dir(DEFAULT_INPUT_DIR.into())
+        .read(day)
+        .unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolve() {
+        assert_eq!(InputSource::Stdin, InputSource::new(Some("-"), None));
+        assert_eq!(
+            InputSource::File("mine/day3".into()),
+            InputSource::new(Some("mine/day3"), Some("other".into()))
+        );
+        assert_eq!(
+            InputSource::Dir("input".into()),
+            InputSource::new(None, None)
+        );
+        assert_eq!(
+            InputSource::Dir("other".into()),
+            InputSource::new(None, Some("other".into()))
+        );
+    }
+
+    #[test]
+    fn read_from_dir() {
+        let input = InputSource::new(None, None).read(1).unwrap();
+        assert!(input.starts_with("141"));
+        assert!(InputSource::new(None, Some("missing".into()))
+            .read(1)
+            .is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//! Just enough JSON writing for the machine readable runner output.
+
+use std::fmt;
+
+/// A flat JSON object, written out on a single line with its keys in insertion order.
+#[derive(Debug, Default)]
+pub struct Object {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn string(mut self, key: &'static str, value: &str) -> Self {
+        self.fields.push((key, quote(value)));
+        self
+    }
+
+    pub fn number(mut self, key: &'static str, value: impl fmt::Display) -> Self {
+        self.fields.push((key, value.to_string()));
+        self
+    }
+
+    pub fn null(mut self, key: &'static str) -> Self {
+        self.fields.push((key, "null".to_string()));
+        self
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}:{}", quote(key), value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// `s` as a JSON string literal.
+pub fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn object() {
+        assert_eq!("{}", Object::new().to_string());
+        assert_eq!(
+            r#"{"day":3,"answer":"a \"b\"\n#.","error":null}"#,
+            Object::new()
+                .number("day", 3)
+                .string("answer", "a \"b\"\n#.")
+                .null("error")
+                .to_string()
+        );
+        assert_eq!(r#""\\\u0001""#, quote("\\\u{1}"));
+    }
+}
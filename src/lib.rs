@@ -0,0 +1,58 @@
+//! Advent of Code 2021 solutions.
+//!
+//! Every day implements [`solution::Solution`]: the puzzle input is parsed once and both
+//! parts are answered from the parsed input. Malformed input is reported as an
+//! [`error::Error`] rather than a panic.
+//!
+//! ```
+//! use aoc_2021::day6::Day6;
+//! use aoc_2021::solution::Solution;
+//!
+//! let fish = Day6::parse("3,4,3,1,2")?;
+//! assert_eq!(5934, Day6::part_one(&fish)?);
+//! # Ok::<(), aoc_2021::error::Error>(())
+//! ```
+//!
+//! The shared parsing helpers live in [`reader`], with nom combinators for the common pieces
+//! of puzzle input in [`parsers`]. Grids are a [`grid::Grid`], with neighbor lookups in
+//! [`neighbors`], and packed bit streams are read and written by [`bits`]. The examples from
+//! the puzzle texts are fixtures, loaded by [`examples`](mod@examples). [`runner`], [`input`],
+//! [`fetch`], [`answers`], [`bench`](mod@bench) and [`json`] back the command line runner.
+//! Diagnostics go through [`log`] rather than straight to stdout.
+
+pub mod answers;
+pub mod bench;
+pub mod bits;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod log;
+pub mod neighbors;
+pub mod parsers;
+pub mod reader;
+pub mod runner;
+pub mod solution;
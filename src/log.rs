@@ -0,0 +1,120 @@
+//! Leveled diagnostics on stderr, so a normal run prints nothing but its answers.
+//!
+//! Solvers log through the [`error!`](crate::error!), [`warn!`](crate::warn!),
+//! [`info!`](crate::info!), [`debug!`](crate::debug!) and [`trace!`](crate::trace!) macros.
+//! Messages below the level set with [`set_level`] are not even formatted.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    #[default]
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    /// The level for `-q` (`quiet`) or a number of `-v` flags on top of the default.
+    pub fn from_flags(quiet: bool, verbose: usize) -> Self {
+        if quiet {
+            Level::Error
+        } else {
+            Self::ALL[usize::min(Level::Warn as usize + verbose, Self::ALL.len() - 1)]
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+/// Sets the most verbose level that is still written out.
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Writes one line, `LEVEL target: message`. Use the macros rather than calling this.
+pub fn log(level: Level, target: &str, args: fmt::Arguments) {
+    if enabled(level) {
+        eprintln!("{} {}: {}", level, target, args);
+    }
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Error, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Warn, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Info, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Debug, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log::log($crate::log::Level::Trace, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels() {
+        assert_eq!(Level::Warn, Level::from_flags(false, 0));
+        assert_eq!(Level::Debug, Level::from_flags(false, 2));
+        assert_eq!(Level::Trace, Level::from_flags(false, 7));
+        assert_eq!(Level::Error, Level::from_flags(true, 0));
+        assert!(Level::Error < Level::Trace);
+
+        assert!(enabled(Level::Warn));
+        assert!(!enabled(Level::Debug));
+    }
+}
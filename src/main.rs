@@ -1,61 +1,263 @@
 use std::env;
-use std::time::Instant;
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod neighbors;
-mod reader;
-
-fn main() {
-    let day_result = {
-        let args: Vec<String> = env::args().collect();
-        if args.len() < 2 {
-            Ok(1)
-        } else {
-            args[1].parse::<u32>()
-        }
-    };
-
-    let now = Instant::now();
-    match day_result {
-        Ok(day) => match day {
-            1 => day1::main(),
-            2 => day2::main(),
-            3 => day3::main(),
-            4 => day4::main(),
-            5 => day5::main(),
-            6 => day6::main(),
-            7 => day7::main(),
-            8 => day8::main(),
-            9 => day9::main(),
-            10 => day10::main(),
-            11 => day11::main(),
-            14 => day14::main(),
-            15 => day15::main(),
-            19 => day19::main(),
-            21 => day21::main(),
-            _ => eprintln!("No such day ({})", day),
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use aoc_2021::answers::{Answers, Tally, ANSWERS_FILE};
+use aoc_2021::bench::{self, Baseline};
+use aoc_2021::day16::Packet;
+use aoc_2021::fetch::{self, BASE_URL, BASE_URL_VAR, SESSION_VAR};
+use aoc_2021::input::{input_dir, InputSource, INPUT_DIR_VAR};
+use aoc_2021::log;
+use aoc_2021::runner;
+
+use cli::{Args, BenchArgs, Command, FetchArgs, Format};
+
+mod cli;
+
+fn read_answers(path: &PathBuf) -> Result<Answers, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Answers::parse(&text).map_err(|e| e.to_string())
+}
+
+fn read_baseline(path: &str) -> Result<Baseline, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    Baseline::parse(&text).map_err(|e| e.to_string())
+}
+
+fn main() -> ExitCode {
+    let (command, level) = match cli::parse_command(env::args().skip(1)) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    log::set_level(level);
+    match command {
+        Command::Run(args) => run(args),
+        Command::Bench(args) => bench(args),
+        Command::Fetch(args) => fetch(args),
+    }
+}
+
+fn run(args: Args) -> ExitCode {
+    let days = runner::select(&args.selection);
+    if days.is_empty() {
+        eprintln!("No such day ({:?})", args.selection);
+        return ExitCode::FAILURE;
+    }
+    let source = InputSource::new(args.input.as_deref(), env::var_os(INPUT_DIR_VAR));
+    if source.is_single() && days.len() > 1 {
+        eprintln!("--input can only be used when running a single day");
+        return ExitCode::FAILURE;
+    }
+    if args.dump {
+        return dump(&args.selection, &source);
+    }
+
+    let answers = if args.check {
+        let path = args.answers.map_or_else(
+            || input_dir(env::var_os(INPUT_DIR_VAR)).join(ANSWERS_FILE),
+            PathBuf::from,
+        );
+        match read_answers(&path) {
+            Ok(answers) => Some(answers),
+            Err(e) => {
+                eprintln!("Could not read answers from {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+
+    let mut reports = Vec::new();
+    let mut tally = Tally::default();
+    let mut failed = false;
+    let report_error = |day: u32, error: String| match args.format {
+        Format::Text => eprintln!("Day {}: {}", day, error),
+        Format::Json => {
+            for part in [1, 2] {
+                println!("{}", runner::error_json(day, part, &error));
+            }
+        }
+    };
+    for day in &days {
+        let input = match source.read(day.number) {
+            Ok(input) => input,
+            Err(e) => {
+                let error = format!("could not read input: {}", e);
+                report_error(day.number, error);
+                failed = true;
+                continue;
+            }
+        };
+        let report = match runner::run(day, &input) {
+            Ok(report) => report,
+            Err(e) => {
+                report_error(day.number, e.to_string());
+                failed = true;
+                continue;
+            }
+        };
+        for (part, part_report) in [(1, &report.part_one), (2, &report.part_two)] {
+            let answer = &part_report.answer;
+            let verdict = answers
+                .as_ref()
+                .map(|answers| answers.verify(report.day, part, answer));
+            if let Some(verdict) = &verdict {
+                tally.add(verdict);
+            }
+            match (args.format, &verdict) {
+                (Format::Json, _) => {
+                    println!(
+                        "{}",
+                        runner::part_json(report.day, part, part_report, verdict.as_ref())
+                    )
+                }
+                (Format::Text, Some(verdict)) => {
+                    println!("Day {}-{}: {} [{}]", report.day, part, answer, verdict)
+                }
+                (Format::Text, None) => println!("Day {}-{}: {}", report.day, part, answer),
+            }
+        }
+        reports.push(report);
+    }
+    if args.format == Format::Text {
+        println!();
+        println!("{}", runner::timing_table(&reports));
+
+        if answers.is_some() {
+            println!();
+            println!("Checked: {}", tally);
+        }
+    }
+    if failed || tally.failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn dump(selection: &RangeInclusive<u32>, source: &InputSource) -> ExitCode {
+    if *selection != (16..=16) {
+        eprintln!("--dump only works for day 16");
+        return ExitCode::FAILURE;
+    }
+    let packet = match source.read(16) {
+        Ok(input) => Packet::from_hex(input.trim()).map_err(|e| e.to_string()),
+        Err(e) => Err(format!("could not read input: {}", e)),
+    };
+    match packet {
+        Ok(packet) => {
+            println!("{}", packet);
+            println!();
+            println!("{:#}", packet);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Day 16: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn bench(args: BenchArgs) -> ExitCode {
+    let day = match runner::select(&(args.day..=args.day)).pop() {
+        Some(day) => day,
+        None => {
+            eprintln!("No such day ({})", args.day);
+            return ExitCode::FAILURE;
+        }
+    };
+    let source = InputSource::new(args.input.as_deref(), env::var_os(INPUT_DIR_VAR));
+    let input = match source.read(day.number) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Day {}: could not read input: {}", day.number, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let baseline = match &args.baseline {
+        Some(path) => match read_baseline(path) {
+            Ok(baseline) => Some(baseline),
+            Err(e) => {
+                eprintln!("Could not read baseline from {}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
         },
-        Err(e) => eprintln!("Could not parse input {:?}", e),
+        None => None,
+    };
+
+    let report = match bench::bench(&day, &input, args.warmup, args.runs) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Day {}: {}", day.number, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Day {}: {} runs after {} warmup",
+        report.day, report.runs, report.warmup
+    );
+    println!();
+    println!("{}", report.table());
+
+    let mut regressed = false;
+    if let Some(baseline) = &baseline {
+        println!();
+        match baseline.compare(&report, args.threshold as f64) {
+            Some(comparison) => {
+                println!("{}", comparison.table());
+                regressed = comparison.regressed();
+            }
+            None => println!("No baseline for day {}", report.day),
+        }
+    }
+
+    if let Some(path) = &args.save {
+        //Other days already in the file are kept
+        let saved = match fs::read_to_string(path) {
+            Ok(text) => Baseline::parse(&text).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(e) => Err(e.to_string()),
+        };
+        let written = saved.and_then(|mut saved| {
+            saved.record(&report);
+            fs::write(path, saved.to_string()).map_err(|e| e.to_string())
+        });
+        match written {
+            Ok(()) => println!("Saved day {} to {}", report.day, path),
+            Err(e) => {
+                eprintln!("Could not save baseline to {}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if regressed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn fetch(args: FetchArgs) -> ExitCode {
+    let session = env::var(SESSION_VAR).unwrap_or_default();
+    let base_url = env::var(BASE_URL_VAR).unwrap_or_else(|_| BASE_URL.to_string());
+    let dir = input_dir(env::var_os(INPUT_DIR_VAR));
+    let transport = fetch::transport_for(&base_url);
+    match fetch::fetch(transport.as_ref(), &base_url, &session, args.day, &dir) {
+        Ok(path) => {
+            println!("Saved day {} to {}", args.day, path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Could not fetch day {}: {}", args.day, e);
+            ExitCode::FAILURE
+        }
     }
-    println!("Running took {} ms", now.elapsed().as_millis());
 }
@@ -1,72 +1,230 @@
+//! Neighbor offsets and neighbor iteration for positions in a rectangular grid, a torus or
+//! the infinite plane, and [stencils](Stencil) for hexagonal grids and 3D space.
+
+use std::ops::Add;
+use std::slice;
+
+use crate::grid::Point;
+
+/// Which cells around a position count as its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// Horizontal and vertical neighbors.
+    Four,
+    /// [`Neighborhood::Four`] and the position itself.
+    FourWithSelf,
+    /// Horizontal, vertical and diagonal neighbors.
+    Eight,
+    /// [`Neighborhood::Eight`] and the position itself.
+    EightWithSelf,
+}
+
+impl Neighborhood {
+    /// Offsets of the neighbors, in reading order.
+    pub fn offsets(self) -> &'static [(i8, i8)] {
+        match self {
+            Neighborhood::Four => &GEN_NEIGHBORS_S,
+            Neighborhood::FourWithSelf => &GEN_NEIGHBORS_S_SELF,
+            Neighborhood::Eight => &GEN_NEIGHBORS,
+            Neighborhood::EightWithSelf => &GEN_NEIGHBORS_SELF,
+        }
+    }
+}
+
+/// The size of a grid whose positions run from `(0, 0)` to `(width - 1, height - 1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Bounds {
+    pub fn new(width: u32, height: u32) -> Self {
+        Bounds { width, height }
+    }
+
+    pub fn contains(self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height
+    }
+}
+
+/// The space positions live in, which decides what lies beyond the edges of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Nothing lies beyond the edges.
+    Bounded(Bounds),
+    /// Leaving the grid on one edge enters it again on the opposite one.
+    Torus(Bounds),
+    /// Every position has all of its neighbors.
+    Infinite,
+}
+
+impl From<Bounds> for Topology {
+    fn from(bounds: Bounds) -> Self {
+        Topology::Bounded(bounds)
+    }
+}
+
+impl Topology {
+    /// Where the neighbor of `pos` that is `offset` away ends up, if it exists at all.
+    pub fn step(self, (x, y): (i32, i32), (dx, dy): (i8, i8)) -> Option<(i32, i32)> {
+        let pos = (x + dx as i32, y + dy as i32);
+        match self {
+            Topology::Bounded(bounds) => bounds.contains(pos).then_some(pos),
+            Topology::Torus(bounds) if bounds.width == 0 || bounds.height == 0 => None,
+            Topology::Torus(bounds) => Some((
+                pos.0.rem_euclid(bounds.width as i32),
+                pos.1.rem_euclid(bounds.height as i32),
+            )),
+            Topology::Infinite => Some(pos),
+        }
+    }
+}
+
+/// Iterator over the neighbors of a position, returned by [`neighbors`].
+#[derive(Debug, Clone)]
+pub struct Neighbors {
+    center: (i32, i32),
+    topology: Topology,
+    offsets: slice::Iter<'static, (i8, i8)>,
+}
+
+impl Iterator for Neighbors {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (center, topology) = (self.center, self.topology);
+        self.offsets
+            .by_ref()
+            .find_map(|offset| topology.step(center, *offset))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.offsets.len()))
+    }
+}
+
+/// The neighbors of `pos` in `neighborhood` that exist in `topology`, in the order of
+/// [`Neighborhood::offsets`]. Passing [`Bounds`] keeps to the grid.
+///
+/// On a torus less than three cells wide or high, the same cell is reached through more
+/// than one offset and comes up more than once.
 pub fn neighbors(
-    (x, y): (i32, i32),
-    pos_type: PosType,
-) -> Box<dyn Iterator<Item = (i32, i32)> + 'static> {
-    let neighbors = match pos_type {
-        PosType::Gen => GEN_NEIGHBORS.iter(),
-        PosType::L => L_NEIGHBORS.iter(),
-        PosType::R => R_NEIGHBORS.iter(),
-        PosType::LT => LT_NEIGHBORS.iter(),
-        PosType::RT => RT_NEIGHBORS.iter(),
-        PosType::LB => LB_NEIGHBORS.iter(),
-        PosType::RB => RB_NEIGHBORS.iter(),
-        PosType::B => B_NEIGHBORS.iter(),
-        PosType::T => T_NEIGHBORS.iter(),
+    pos: (i32, i32),
+    topology: impl Into<Topology>,
+    neighborhood: Neighborhood,
+) -> Neighbors {
+    Neighbors {
+        center: pos,
+        topology: topology.into(),
+        offsets: neighborhood.offsets().iter(),
+    }
+}
+
+/// A position moved by each of a fixed list of offsets. Unlike [`Neighbors`] nothing is left
+/// out, the hexagonal grid and 3D space have no edges.
+#[derive(Debug, Clone)]
+pub struct Stencil<P: 'static> {
+    center: P,
+    offsets: slice::Iter<'static, P>,
+}
+
+impl<P: Copy + Add<Output = P>> Iterator for Stencil<P> {
+    type Item = P;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let center = self.center;
+        self.offsets.next().map(|offset| center + *offset)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+impl<P: Copy + Add<Output = P>> ExactSizeIterator for Stencil<P> {}
+
+/// A cell of a hexagonal grid in axial coordinates. The third cube coordinate `s` follows
+/// from `q + r + s = 0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    pub const fn new(q: i32, r: i32) -> Self {
+        Hex { q, r }
+    }
+
+    /// The cell at cube coordinates `(q, r, s)`, if they add up to zero.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Option<Self> {
+        (q + r + s == 0).then_some(Hex { q, r })
+    }
+
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The number of steps between two cells.
+    pub fn distance(self, other: Hex) -> u32 {
+        let (dq, dr, ds) = (self.q - other.q, self.r - other.r, self.s() - other.s());
+        dq.unsigned_abs()
+            .max(dr.unsigned_abs())
+            .max(ds.unsigned_abs())
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexNeighborhood {
+    /// The cells sharing an edge.
+    Six,
+    /// [`HexNeighborhood::Six`] and the cell itself.
+    SixWithSelf,
+}
+
+/// The neighbors of `hex`, going round from `+q` counterclockwise after the cell itself when
+/// that is included.
+pub fn hex_neighbors(hex: Hex, neighborhood: HexNeighborhood) -> Stencil<Hex> {
+    let offsets: &'static [Hex] = match neighborhood {
+        HexNeighborhood::Six => &HEX_NEIGHBORS,
+        HexNeighborhood::SixWithSelf => &HEX_NEIGHBORS_SELF,
     };
-    Box::new(neighbors.map(move |(o_x, o_y)| (x + *o_x as i32, y + *o_y as i32)))
-}
-
-pub fn neighbors_straight(
-    (x, y): (i32, i32),
-    pos_type: PosType,
-) -> Box<dyn Iterator<Item = (i32, i32)> + 'static> {
-    let neighbors = match pos_type {
-        PosType::Gen => GEN_NEIGHBORS_S.iter(),
-        PosType::L => L_NEIGHBORS_S.iter(),
-        PosType::R => R_NEIGHBORS_S.iter(),
-        PosType::LT => LT_NEIGHBORS_S.iter(),
-        PosType::RT => RT_NEIGHBORS_S.iter(),
-        PosType::LB => LB_NEIGHBORS_S.iter(),
-        PosType::RB => RB_NEIGHBORS_S.iter(),
-        PosType::B => B_NEIGHBORS_S.iter(),
-        PosType::T => T_NEIGHBORS_S.iter(),
+    Stencil {
+        center: hex,
+        offsets: offsets.iter(),
+    }
+}
+
+/// Which points around a point in 3D space count as its neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood3d {
+    /// The points sharing a face of the cube.
+    Six,
+    /// The points sharing a face or an edge.
+    Eighteen,
+    /// The points sharing a face, an edge or a corner.
+    TwentySix,
+}
+
+/// The neighbors of `point`, ordered by `z`, then `y`, then `x`.
+pub fn neighbors_3d(point: Point, neighborhood: Neighborhood3d) -> Stencil<Point> {
+    let offsets: &'static [Point] = match neighborhood {
+        Neighborhood3d::Six => &NEIGHBORS_3D_6,
+        Neighborhood3d::Eighteen => &NEIGHBORS_3D_18,
+        Neighborhood3d::TwentySix => &NEIGHBORS_3D_26,
     };
-    Box::new(neighbors.map(move |(o_x, o_y)| (x + *o_x as i32, y + *o_y as i32)))
-}
-
-pub enum PosType {
-    Gen,
-    L,
-    R,
-    LT,
-    RT,
-    LB,
-    RB,
-    B,
-    T,
-}
-
-impl PosType {
-    pub fn from_index(index: u32, (row_size, col_size): (u32, u32)) -> ((i32, i32), Self) {
-        let x = (index % row_size) as i32;
-        let y = (index / row_size) as i32;
-        ((x, y), Self::from((x, y), (row_size, col_size)))
-    }
-    pub fn from(pos: (i32, i32), (row_size, col_size): (u32, u32)) -> Self {
-        let max_x = (row_size - 1) as i32;
-        let max_y = (col_size - 1) as i32;
-
-        match pos {
-            (0, 0) => PosType::LT,
-            (x, 0) if x == max_x => PosType::RT,
-            (_, 0) => PosType::T,
-            (0, y) if y == max_y => PosType::LB,
-            (x, y) if y == max_y && x == max_x => PosType::RB,
-            (_, y) if y == max_y => PosType::B,
-            (0, _) => PosType::L,
-            (x, _) if x == max_x => PosType::R,
-            (_, _) => PosType::Gen,
-        }
+    Stencil {
+        center: point,
+        offsets: offsets.iter(),
     }
 }
 
@@ -95,24 +253,235 @@ pub const GEN_NEIGHBORS: [(i8, i8); 8] = [
     (1, 1),
 ];
 
-const R_NEIGHBORS: [(i8, i8); 5] = [(-1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
-const L_NEIGHBORS: [(i8, i8); 5] = [(0, -1), (1, -1), (1, 0), (0, 1), (1, 1)];
-const T_NEIGHBORS: [(i8, i8); 5] = [(-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
-const B_NEIGHBORS: [(i8, i8); 5] = [(-1, 0), (1, 0), (-1, -1), (0, -1), (1, -1)];
-const LT_NEIGHBORS: [(i8, i8); 3] = [(1, 0), (0, 1), (1, 1)];
-const RT_NEIGHBORS: [(i8, i8); 3] = [(-1, 0), (-1, 1), (0, 1)];
-const RB_NEIGHBORS: [(i8, i8); 3] = [(-1, -1), (0, -1), (-1, 0)];
-const LB_NEIGHBORS: [(i8, i8); 3] = [(0, -1), (1, -1), (1, 0)];
-
 ///NO DIAGONALS
-
 pub const GEN_NEIGHBORS_S: [(i8, i8); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
 
-const R_NEIGHBORS_S: [(i8, i8); 3] = [(0, -1), (-1, 0), (0, 1)];
-const L_NEIGHBORS_S: [(i8, i8); 3] = [(0, -1), (1, 0), (0, 1)];
-const T_NEIGHBORS_S: [(i8, i8); 3] = [(-1, 0), (1, 0), (0, 1)];
-const B_NEIGHBORS_S: [(i8, i8); 3] = [(-1, 0), (1, 0), (0, -1)];
-const LT_NEIGHBORS_S: [(i8, i8); 2] = [(1, 0), (0, 1)];
-const RT_NEIGHBORS_S: [(i8, i8); 2] = [(-1, 0), (0, 1)];
-const RB_NEIGHBORS_S: [(i8, i8); 2] = [(0, -1), (-1, 0)];
-const LB_NEIGHBORS_S: [(i8, i8); 2] = [(0, -1), (1, 0)];
+///NO DIAGONALS, YES SELF
+pub const GEN_NEIGHBORS_S_SELF: [(i8, i8); 5] = [(0, -1), (-1, 0), (0, 0), (1, 0), (0, 1)];
+
+///HEX, axial
+pub const HEX_NEIGHBORS: [Hex; 6] = [
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+///HEX, axial, YES SELF
+pub const HEX_NEIGHBORS_SELF: [Hex; 7] = [
+    Hex::new(0, 0),
+    Hex::new(1, 0),
+    Hex::new(1, -1),
+    Hex::new(0, -1),
+    Hex::new(-1, 0),
+    Hex::new(-1, 1),
+    Hex::new(0, 1),
+];
+
+/// The offsets around the origin of the unit cube that are non-zero along at most `axes` axes.
+const fn cube_offsets<const N: usize>(axes: u32) -> [Point; N] {
+    let mut offsets = [Point::new(0, 0, 0); N];
+    let mut i = 0;
+    let mut z = -1;
+    while z <= 1 {
+        let mut y = -1;
+        while y <= 1 {
+            let mut x = -1;
+            while x <= 1 {
+                let moved = (x != 0) as u32 + (y != 0) as u32 + (z != 0) as u32;
+                if moved > 0 && moved <= axes {
+                    offsets[i] = Point::new(x, y, z);
+                    i += 1;
+                }
+                x += 1;
+            }
+            y += 1;
+        }
+        z += 1;
+    }
+    assert!(i == N, "wrong number of offsets");
+    offsets
+}
+
+///3D, faces
+pub const NEIGHBORS_3D_6: [Point; 6] = cube_offsets(1);
+
+///3D, faces and edges
+pub const NEIGHBORS_3D_18: [Point; 18] = cube_offsets(2);
+
+///3D, faces, edges and corners
+pub const NEIGHBORS_3D_26: [Point; 26] = cube_offsets(3);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn around(pos: (i32, i32), (width, height): (u32, u32), n: Neighborhood) -> Vec<(i32, i32)> {
+        neighbors(pos, Bounds::new(width, height), n).collect()
+    }
+
+    /// How many neighbors every cell of the grid has, row by row.
+    fn counts((width, height): (u32, u32), n: Neighborhood) -> Vec<usize> {
+        (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| (x, y)))
+            .map(|pos| around(pos, (width, height), n).len())
+            .collect()
+    }
+
+    #[test]
+    fn borders() {
+        assert_eq!(
+            vec![3, 5, 3, 5, 8, 5, 3, 5, 3],
+            counts((3, 3), Neighborhood::Eight)
+        );
+        assert_eq!(
+            vec![2, 3, 2, 3, 4, 3, 2, 3, 2],
+            counts((3, 3), Neighborhood::Four)
+        );
+        assert_eq!(
+            vec![4, 6, 4, 6, 9, 6, 4, 6, 4],
+            counts((3, 3), Neighborhood::EightWithSelf)
+        );
+        //Every corner of a wider than high grid
+        assert_eq!(
+            vec![3, 5, 5, 3, 3, 5, 5, 3],
+            counts((4, 2), Neighborhood::Eight)
+        );
+        assert_eq!(
+            vec![(2, 0), (3, 0), (2, 1)],
+            around((3, 1), (4, 2), Neighborhood::Eight)
+        );
+        assert_eq!(
+            vec![(0, 3), (1, 3), (1, 4)],
+            around((0, 4), (2, 5), Neighborhood::Eight)
+        );
+    }
+
+    #[test]
+    fn thin_grids() {
+        assert_eq!(vec![0], counts((1, 1), Neighborhood::Eight));
+        assert_eq!(
+            vec![(0, 0)],
+            around((0, 0), (1, 1), Neighborhood::FourWithSelf)
+        );
+        assert_eq!(vec![1, 2, 2, 1], counts((4, 1), Neighborhood::Eight));
+        assert_eq!(vec![1, 2, 2, 1], counts((1, 4), Neighborhood::Four));
+        assert_eq!(
+            vec![(0, 1), (0, 3)],
+            around((0, 2), (1, 4), Neighborhood::Eight)
+        );
+        assert_eq!(
+            vec![(1, 0), (2, 0), (3, 0)],
+            around((2, 0), (4, 1), Neighborhood::EightWithSelf)
+        );
+    }
+
+    #[test]
+    fn outside() {
+        assert!(around((0, 0), (0, 0), Neighborhood::Eight).is_empty());
+        assert_eq!(vec![(0, 0)], around((-1, -1), (3, 3), Neighborhood::Eight));
+        assert_eq!(vec![(2, 2)], around((3, 2), (3, 3), Neighborhood::Four));
+    }
+
+    #[test]
+    fn torus() {
+        let torus = Topology::Torus(Bounds::new(4, 3));
+        assert_eq!(
+            vec![(0, 2), (3, 0), (1, 0), (0, 1)],
+            neighbors((0, 0), torus, Neighborhood::Four).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                (2, 1),
+                (3, 1),
+                (0, 1),
+                (2, 2),
+                (0, 2),
+                (2, 0),
+                (3, 0),
+                (0, 0)
+            ],
+            neighbors((3, 2), torus, Neighborhood::Eight).collect::<Vec<_>>()
+        );
+        //A single row wraps onto itself vertically
+        let row = Topology::Torus(Bounds::new(3, 1));
+        assert_eq!(
+            vec![(1, 0), (0, 0), (2, 0), (1, 0)],
+            neighbors((1, 0), row, Neighborhood::Four).collect::<Vec<_>>()
+        );
+        let empty = Topology::Torus(Bounds::new(0, 0));
+        assert_eq!(0, neighbors((0, 0), empty, Neighborhood::Eight).count());
+    }
+
+    #[test]
+    fn infinite() {
+        assert_eq!(
+            vec![(-5, -1), (-6, 0), (-4, 0), (-5, 1)],
+            neighbors((-5, 0), Topology::Infinite, Neighborhood::Four).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            9,
+            neighbors((0, 0), Topology::Infinite, Neighborhood::EightWithSelf).count()
+        );
+    }
+
+    #[test]
+    fn hex() {
+        let center = Hex::new(2, -1);
+        let around: Vec<_> = hex_neighbors(center, HexNeighborhood::Six).collect();
+        assert_eq!(
+            vec![
+                Hex::new(3, -1),
+                Hex::new(3, -2),
+                Hex::new(2, -2),
+                Hex::new(1, -1),
+                Hex::new(1, 0),
+                Hex::new(2, 0)
+            ],
+            around
+        );
+        assert!(around.iter().all(|h| h.distance(center) == 1));
+        assert_eq!(
+            Some(center),
+            hex_neighbors(center, HexNeighborhood::SixWithSelf).next()
+        );
+        assert_eq!(Some(Hex::new(1, 2)), Hex::from_cube(1, 2, -3));
+        assert_eq!(None, Hex::from_cube(1, 2, 3));
+        assert_eq!(-1, center.s());
+        assert_eq!(3, Hex::new(0, 0).distance(Hex::new(3, -3)));
+        assert_eq!(4, Hex::new(-1, -1).distance(Hex::new(1, 1)));
+    }
+
+    #[test]
+    fn space() {
+        let origin = Point::new(0, 0, 0);
+        let count = |n| neighbors_3d(origin, n).count();
+        assert_eq!(6, count(Neighborhood3d::Six));
+        assert_eq!(18, count(Neighborhood3d::Eighteen));
+        assert_eq!(26, count(Neighborhood3d::TwentySix));
+        assert!(!neighbors_3d(origin, Neighborhood3d::TwentySix).any(|p| p == origin));
+
+        let point = Point::new(10, -4, 7);
+        assert_eq!(
+            vec![
+                Point::new(10, -4, 6),
+                Point::new(10, -5, 7),
+                Point::new(9, -4, 7),
+                Point::new(11, -4, 7),
+                Point::new(10, -3, 7),
+                Point::new(10, -4, 8)
+            ],
+            neighbors_3d(point, Neighborhood3d::Six).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some(Point::new(9, -5, 6)),
+            neighbors_3d(point, Neighborhood3d::TwentySix).next()
+        );
+        assert_eq!(
+            Some(Point::new(10, -5, 6)),
+            neighbors_3d(point, Neighborhood3d::Eighteen).next()
+        );
+    }
+}
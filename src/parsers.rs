@@ -0,0 +1,120 @@
+//! nom combinators for the pieces puzzle inputs are made of, so the parser of a day reads like
+//! the format it parses.
+//!
+//! Run them through [`parse_all`](crate::error::parse_all), line by line with
+//! [`parse_lines`](crate::error::parse_lines), to turn a failure into an
+//! [`Error`](crate::error::Error) that points at where the parser gave up. Character grids,
+//! whose rows must all be as wide as the first, are read by
+//! [`parse_char_grid`](crate::reader::parse_char_grid) instead.
+//!
+//! ```
+//! use aoc_2021::error::{parse_all, parse_lines};
+//! use aoc_2021::parsers::{arrow, coordinates};
+//!
+//! let segments = parse_lines("0,9 -> 5,9\n8,0 -> 0,8\n", |line| {
+//!     parse_all(line, arrow(coordinates, coordinates))
+//! })?;
+//! assert_eq!(vec![((0, 9), (5, 9)), ((8, 0), (0, 8))], segments);
+//! # Ok::<(), aoc_2021::error::Error>(())
+//! ```
+
+use std::str::FromStr;
+
+use nom::bytes::complete::{tag, take_until};
+use nom::character::complete::{char, digit1, space0};
+use nom::combinator::{map_res, opt, recognize};
+use nom::sequence::{delimited, pair, preceded, separated_pair, tuple};
+use nom::IResult;
+
+/// A decimal integer with an optional leading `-`, like `42` or `-10`.
+pub fn integer<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+fn comma(input: &str) -> IResult<&str, char> {
+    delimited(space0, char(','), space0)(input)
+}
+
+/// A pair of integers separated by a comma, like `0,9` or `-1, 2`.
+pub fn coordinates<T: FromStr>(input: &str) -> IResult<&str, (T, T)> {
+    separated_pair(integer, comma, integer)(input)
+}
+
+/// Three integers separated by commas, like `-618,-824,-621`.
+pub fn triple<T: FromStr>(input: &str) -> IResult<&str, (T, T, T)> {
+    let (input, (x, _, y, _, z)) = tuple((integer, comma, integer, comma, integer))(input)?;
+    Ok((input, (x, y, z)))
+}
+
+/// Two integers separated by `..`, like `-10..-5`.
+pub fn range<T: FromStr>(input: &str) -> IResult<&str, (T, T)> {
+    separated_pair(integer, tag(".."), integer)(input)
+}
+
+/// `from -> to`, like `0,9 -> 5,9` or `CH -> B`.
+pub fn arrow<'a, A, B>(
+    from: impl FnMut(&'a str) -> IResult<&'a str, A>,
+    to: impl FnMut(&'a str) -> IResult<&'a str, B>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (A, B)> {
+    separated_pair(from, delimited(space0, tag("->"), space0), to)
+}
+
+/// The value of `key=value`, like `x=20..30` or `fold along y=7`.
+pub fn key_value<'a, T>(
+    key: &'static str,
+    value: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    preceded(pair(tag(key), char('=')), value)
+}
+
+/// The title of a header line like `--- scanner 0 ---`, here `scanner 0`.
+pub fn header(input: &str) -> IResult<&str, &str> {
+    delimited(tag("--- "), take_until(" ---"), tag(" ---"))(input)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::{parse_all, parse_lines};
+
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Ok(("", -10)), integer::<i32>("-10"));
+        assert_eq!(Ok((",2", 7)), integer::<u8>("7,2"));
+        assert!(integer::<u8>("-7").is_err());
+        assert!(integer::<u8>("300").is_err());
+        assert_eq!(Ok(("", (-1, 2))), coordinates::<i32>("-1, 2"));
+        assert_eq!(Ok(("", (1, -2, 3))), triple::<i64>("1,-2,3"));
+        assert_eq!(Ok(("", (-10, -5))), range::<i32>("-10..-5"));
+    }
+
+    #[test]
+    fn pieces() {
+        let mut segment = arrow(coordinates::<u32>, coordinates::<u32>);
+        assert_eq!(Ok(("", ((0, 9), (5, 9)))), segment("0,9 -> 5,9"));
+        assert_eq!(
+            Ok(("", 7)),
+            key_value("fold along y", integer::<u32>)("fold along y=7")
+        );
+        assert_eq!(Ok(("", (20, 30))), key_value("x", range::<i32>)("x=20..30"));
+        assert_eq!(
+            Ok(("\n0,2", "scanner 12")),
+            header("--- scanner 12 ---\n0,2")
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let point = |line: &str| parse_all(line, coordinates::<u8>);
+        assert_eq!(Ok(vec![(1, 2), (3, 4)]), parse_lines("1,2\n\n3,4", point));
+        assert_eq!(
+            "parse error at line 3, column 3: unexpected \"x\" (Digit)",
+            parse_lines("1,2\n\n3,x", point).unwrap_err().to_string()
+        );
+        assert_eq!(
+            "parse error at line 1, column 4: unexpected \" 2\"",
+            parse_lines("1,2 2", point).unwrap_err().to_string()
+        );
+    }
+}
@@ -1,51 +1,376 @@
-use std::fs::File;
-use std::io::{self, BufRead};
-use std::path::Path;
+//! Helpers turning raw puzzle input into the shapes most days start from.
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+use std::fmt;
+use std::io::{BufRead, Read};
+use std::str::FromStr;
+
+use crate::error::{Error, Location, Result};
+use crate::grid::Grid;
+
+/// Parses the numbers in `input`, separated by commas, whitespace or both, like `1,20,3` or
+/// `22  8 0`. Errors point at the first token that is not a `T`.
+pub fn parse_numbers<T>(input: &str) -> Result<Vec<T>>
 where
-    P: AsRef<Path>,
+    T: FromStr,
+    T::Err: fmt::Display,
 {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+    let is_separator = |c: char| c == ',' || c.is_whitespace();
+    let mut numbers = Vec::new();
+    let mut rest = input.trim_start_matches(is_separator);
+    while !rest.is_empty() {
+        let end = rest.find(is_separator).unwrap_or(rest.len());
+        numbers.push(parse_number(input, rest, &rest[..end])?);
+        rest = rest[end..].trim_start_matches(is_separator);
+    }
+    Ok(numbers)
 }
 
-pub fn parse_ints(line: &str, separator: &str) -> Vec<u32> {
-    line.split(separator)
-        .filter_map(|v| v.parse::<u32>().ok())
-        .collect()
+/// Every integer in free text, like the four in `target area: x=20..30, y=-10..-5`. A `-`
+/// right before the digits makes a number negative, unless it follows another number.
+/// Errors point at numbers that do not fit in a `T`.
+pub fn extract_numbers<T>(text: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut numbers = Vec::new();
+    let mut previous = None;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let signed = c == '-'
+            && rest[1..].starts_with(|c: char| c.is_ascii_digit())
+            && !previous.is_some_and(|p: char| p.is_ascii_digit());
+        if c.is_ascii_digit() || signed {
+            let end = rest[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(rest.len(), |i| i + 1);
+            numbers.push(parse_number(text, rest, &rest[..end])?);
+            previous = rest[..end].chars().last();
+            rest = &rest[end..];
+        } else {
+            previous = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    Ok(numbers)
 }
 
-pub fn split_lines(input: &str) -> Vec<String> {
-    input.split("\n").map(|l| l.trim().to_string()).collect()
+/// Parses `token`, which starts `rest` of `input`.
+fn parse_number<T>(input: &str, rest: &str, token: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token.parse().map_err(|e| {
+        Error::parse(format!("invalid number {:?}: {}", token, e)).at(Location::of(input, rest))
+    })
 }
 
-pub fn parse_grid(raw_input: &str) -> ((usize, usize), Vec<u32>) {
-    parse_grid_lines(raw_input.split("\n").map(|l| l.to_string()).collect())
+/// Parses a block of digits into `((row_size, col_size), cells)`, with cells stored row by row.
+pub fn parse_grid(raw_input: &str) -> Result<((usize, usize), Vec<u32>)> {
+    let grid = parse_digit_grid(raw_input)?;
+    Ok(((grid.width(), grid.height()), grid.into_cells()))
 }
 
-pub fn parse_grid_lines(input: Vec<String>) -> ((usize, usize), Vec<u32>) {
-    let col_size = input.len();
+/// Like [`parse_grid`] into a [`Grid`].
+pub fn parse_digit_grid(input: &str) -> Result<Grid<u32>> {
+    parse_char_grid(input, "a digit", |c| c.to_digit(10))
+}
 
-    let parsed: Vec<u32> = input
-        .iter()
-        .map(|l| l.chars().filter_map(|c| c.to_digit(10)))
-        .flatten()
+/// Parses a block of characters into a [`Grid`], turning each into a cell with `cell`.
+///
+/// `expected` describes the characters `cell` accepts, for the error when it turns one down.
+/// Blank lines around the block and whitespace around the rows are skipped, so indented
+/// literals parse too, but every row must be as wide as the first.
+pub fn parse_char_grid<T>(
+    input: &str,
+    expected: &str,
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> Result<Grid<T>> {
+    let rows: Vec<(usize, &str)> = numbered_lines(input)
+        .skip_while(|(_, line)| line.trim().is_empty())
         .collect();
+    let end = rows
+        .iter()
+        .rposition(|(_, line)| !line.trim().is_empty())
+        .map_or(0, |last| last + 1);
+    let rows = &rows[..end];
+    let width = rows
+        .first()
+        .map_or(0, |(_, row)| row.trim().chars().count());
+    if width == 0 {
+        return Err(Error::invalid("the grid is empty"));
+    }
 
-    let row_size = parsed.len() / col_size;
-    let col_size = input.len();
+    let mut cells = Vec::with_capacity(width * rows.len());
+    for &(y, line) in rows {
+        let row = line.trim();
+        let indent = line.len() - line.trim_start().len();
+        for (x, c) in row.chars().enumerate() {
+            let value = cell(c).ok_or_else(|| {
+                Error::parse(format!("expected {}, found {:?}", expected, c))
+                    .at(Location::new(y, line[..indent].chars().count() + x + 1))
+            })?;
+            cells.push(value);
+        }
+        match row.chars().count() {
+            0 => return Err(Error::invalid("blank line inside the grid").on_line(y)),
+            found if found != width => {
+                let message = format!(
+                    "expected {} columns like the first row, found {}",
+                    width, found
+                );
+                return Err(Error::invalid(message).on_line(y));
+            }
+            _ => {}
+        }
+    }
+    Grid::new(width, rows.len(), cells)
+}
 
-    ((row_size, col_size), parsed)
+/// Reads all of `reader` into one buffer, for the borrowing iterators below to work on.
+pub fn read_to_string(mut reader: impl Read) -> Result<String> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+    Ok(input)
 }
 
-pub fn read_lines_filter_ok<P>(filename: P) -> Vec<String>
-where
-    P: AsRef<Path>,
-{
-    read_lines(filename)
-        .unwrap()
-        .filter_map(|l| l.ok())
-        .filter(|l| l.len() > 0)
-        .collect()
+/// The lines of `reader` as they come in, passing on read errors instead of dropping them.
+pub fn read_lines(reader: impl BufRead) -> impl Iterator<Item = Result<String>> {
+    reader.lines().map(|line| Ok(line?))
+}
+
+/// The blank line separated sections of `reader` as they come in, one `Vec` of lines each.
+pub fn read_paragraphs(reader: impl BufRead) -> impl Iterator<Item = Result<Vec<String>>> {
+    let mut lines = reader.lines();
+    std::iter::from_fn(move || {
+        let mut paragraph = Vec::new();
+        for line in lines.by_ref() {
+            match line {
+                Err(e) => return Some(Err(e.into())),
+                Ok(line) if line.trim().is_empty() && paragraph.is_empty() => continue,
+                Ok(line) if line.trim().is_empty() => break,
+                Ok(line) => paragraph.push(line),
+            }
+        }
+        (!paragraph.is_empty()).then_some(Ok(paragraph))
+    })
+}
+
+/// The lines of `input` with their 1-based line numbers, borrowed from `input`.
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate().map(|(i, line)| (i + 1, line))
+}
+
+/// A run of lines that are not blank, as found between the blank lines of block structured
+/// input like the bingo boards of day 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Paragraph<'a> {
+    /// The line number of the first line.
+    pub line: usize,
+    /// The lines, without the line ending of the last one.
+    pub text: &'a str,
+}
+
+impl<'a> Paragraph<'a> {
+    pub fn lines(&self) -> impl Iterator<Item = &'a str> {
+        self.text.lines()
+    }
+
+    /// The lines with their line numbers in the whole input.
+    pub fn numbered_lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let first = self.line;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(i, line)| (first + i, line))
+    }
+}
+
+/// Iterator over the [`Paragraph`]s of a buffer, returned by [`paragraphs`].
+#[derive(Debug, Clone)]
+pub struct Paragraphs<'a> {
+    rest: &'a str,
+    line: usize,
+}
+
+impl<'a> Iterator for Paragraphs<'a> {
+    type Item = Paragraph<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut start = None;
+        let mut end = 0;
+        let mut offset = 0;
+        for line in self.rest.split_inclusive('\n') {
+            let blank = line.trim().is_empty();
+            match (start, blank) {
+                (None, true) => self.line += 1,
+                (None, false) => start = Some((offset, self.line)),
+                (Some(_), true) => break,
+                (Some(_), false) => {}
+            }
+            if !blank {
+                end = offset + line.trim_end_matches(['\r', '\n']).len();
+            }
+            offset += line.len();
+        }
+        let (start, line) = start?;
+        let text = &self.rest[start..end];
+        self.line = line + text.lines().count();
+        self.rest = &self.rest[offset..];
+        Some(Paragraph { line, text })
+    }
+}
+
+/// The blank line separated sections of `input`, borrowed from `input`. Lines holding only
+/// whitespace count as blank, and any number of them separate two paragraphs.
+pub fn paragraphs(input: &str) -> Paragraphs<'_> {
+    Paragraphs {
+        rest: input,
+        line: 1,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        assert_eq!(Ok(vec![1, 20, 3]), parse_numbers::<u32>("1,20,3\n"));
+        assert_eq!(Ok(vec![22, 8, 0]), parse_numbers::<u8>("22  8 0"));
+        assert_eq!(Ok(vec![-3, 4, 0]), parse_numbers::<i64>(" -3, 4,,\t0 "));
+        assert_eq!(Ok(Vec::<u32>::new()), parse_numbers(""));
+        assert_eq!(
+            "parse error at line 1, column 4: invalid number \"x\": invalid digit found in string",
+            parse_numbers::<u32>("1, x,3").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "parse error at line 2, column 3: invalid number \"-1\": invalid digit found in string",
+            parse_numbers::<u32>("1\n2 -1").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn extract() {
+        assert_eq!(
+            Ok(vec![20, 30, -10, -5]),
+            extract_numbers::<i32>("target area: x=20..30, y=-10..-5")
+        );
+        assert_eq!(Ok(vec![1, 2, 3]), extract_numbers::<u32>("pos 1-2, é3"));
+        assert_eq!(Ok(Vec::<i32>::new()), extract_numbers("no numbers - here"));
+        assert_eq!(
+            "parse error at line 1, column 13: invalid number \"-10\": invalid digit found in string",
+            extract_numbers::<u32>("x=20..30, y=-10..-5")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "parse error at line 1, column 3: invalid number \"300\": number too large to fit in target type",
+            extract_numbers::<u8>("x=300").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn digit_grid() {
+        let grid = parse_digit_grid("123\r\n456\n\n").unwrap();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(&[1, 2, 3, 4, 5, 6], grid.cells());
+        assert_eq!(
+            "parse error at line 2, column 2: expected a digit, found 'x'",
+            parse_digit_grid("123\n4x6").unwrap_err().to_string()
+        );
+        assert_eq!(
+            "invalid input at line 2: expected 3 columns like the first row, found 2",
+            parse_digit_grid("123\n45").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn char_grid() {
+        let grid = parse_char_grid("\n    #.#\n    ..#\n", "'#' or '.'", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        });
+        let grid = grid.unwrap();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(&[true, false, true, false, false, true], grid.cells());
+        assert_eq!(
+            "parse error at line 2, column 4: expected 'a' or 'b', found 'c'",
+            parse_char_grid("ab\n  bc", "'a' or 'b'", |c| "ab".find(c))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid input at line 2: blank line inside the grid",
+            parse_char_grid("ab\n\nba", "'a' or 'b'", |c| "ab".find(c))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid input at line 3: expected 2 columns like the first row, found 3",
+            parse_char_grid(" ab\r\n ba\r\n aba\r\n", "'a' or 'b'", |c| "ab".find(c))
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "invalid input: the grid is empty",
+            parse_digit_grid("\n  \n").unwrap_err().to_string()
+        );
+        assert_eq!(Ok(((2, 2), vec![1, 2, 3, 4])), parse_grid("12\n34"));
+    }
+
+    #[test]
+    fn blocks() {
+        let input = "\n7,4,9\r\n\r\n22 13\n 8  2\n  \n\n\n1 2\n";
+        let found: Vec<_> = paragraphs(input).collect();
+        assert_eq!(
+            vec![
+                Paragraph {
+                    line: 2,
+                    text: "7,4,9"
+                },
+                Paragraph {
+                    line: 4,
+                    text: "22 13\n 8  2"
+                },
+                Paragraph {
+                    line: 9,
+                    text: "1 2"
+                }
+            ],
+            found
+        );
+        assert_eq!(
+            vec![(4, "22 13"), (5, " 8  2")],
+            found[1].numbered_lines().collect::<Vec<_>>()
+        );
+        assert_eq!(0, paragraphs(" \n\n").count());
+        assert_eq!(
+            vec![(1, "a"), (2, ""), (3, "b")],
+            numbered_lines("a\n\nb\n").collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn streaming() {
+        let input = "a\nb\n\n\nc\n".as_bytes();
+        assert_eq!(
+            vec![
+                Ok(vec!["a".to_string(), "b".to_string()]),
+                Ok(vec!["c".to_string()])
+            ],
+            read_paragraphs(input).collect::<Vec<_>>()
+        );
+        assert_eq!(5, read_lines(input).count());
+        assert_eq!(Ok("a\nb\n\n\nc\n".to_string()), read_to_string(input));
+
+        //Invalid UTF-8 is an error rather than a missing line
+        let input = &b"a\n\xff\nc"[..];
+        let lines: Vec<_> = read_lines(input).collect();
+        assert_eq!(Ok("a".to_string()), lines[0]);
+        assert!(matches!(lines[1], Err(Error::Io(_))));
+        assert!(read_to_string(input).is_err());
+    }
 }
@@ -0,0 +1,256 @@
+//! Running selected days and reporting their answers and timings.
+
+use std::num::ParseIntError;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use crate::answers::Verdict;
+use crate::error::Result;
+use crate::json::Object;
+use crate::solution::{registry, Day};
+
+/// Parses `all`, a single day (`5`) or a range of days (`1..10`, `1..=10`).
+pub fn parse_selection(arg: &str) -> std::result::Result<RangeInclusive<u32>, ParseIntError> {
+    if arg == "all" {
+        return Ok(1..=25);
+    }
+    if let Some((start, end)) = arg.split_once("..=") {
+        return Ok(start.parse()?..=end.parse()?);
+    }
+    if let Some((start, end)) = arg.split_once("..") {
+        let end: u32 = end.parse()?;
+        return Ok(start.parse()?..=end.saturating_sub(1));
+    }
+    let day = arg.parse()?;
+    Ok(day..=day)
+}
+
+pub fn select(days: &RangeInclusive<u32>) -> Vec<Day> {
+    registry()
+        .into_iter()
+        .filter(|d| days.contains(&d.number))
+        .collect()
+}
+
+pub struct PartReport {
+    pub answer: String,
+    pub duration: Duration,
+}
+
+pub struct DayReport {
+    pub day: u32,
+    pub parse: Duration,
+    pub part_one: PartReport,
+    pub part_two: PartReport,
+}
+
+impl DayReport {
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_one.duration + self.part_two.duration
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let now = Instant::now();
+    let result = f();
+    (result, now.elapsed())
+}
+
+pub fn run(day: &Day, input: &str) -> Result<DayReport> {
+    let (parsed, parse) = timed(|| day.runner.parse(input));
+    let parsed = parsed?;
+    let (answer, duration) = timed(|| parsed.part_one());
+    let part_one = PartReport {
+        answer: answer?,
+        duration,
+    };
+    let (answer, duration) = timed(|| parsed.part_two());
+    let part_two = PartReport {
+        answer: answer?,
+        duration,
+    };
+    Ok(DayReport {
+        day: day.number,
+        parse,
+        part_one,
+        part_two,
+    })
+}
+
+pub(crate) fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000f64
+}
+
+pub(crate) fn format_duration(duration: Duration) -> String {
+    format!("{:.3} ms", millis(duration))
+}
+
+/// Renders the timings of all reports as an aligned table, closed by a grand total.
+pub fn timing_table(reports: &[DayReport]) -> String {
+    let header = ["Day", "Parse", "Part 1", "Part 2", "Total"].map(String::from);
+    let rows: Vec<[String; 5]> = reports
+        .iter()
+        .map(|r| {
+            [
+                r.day.to_string(),
+                format_duration(r.parse),
+                format_duration(r.part_one.duration),
+                format_duration(r.part_two.duration),
+                format_duration(r.total()),
+            ]
+        })
+        .collect();
+    let sum = |f: fn(&DayReport) -> Duration| reports.iter().map(f).sum::<Duration>();
+    let total = [
+        "Total".to_string(),
+        format_duration(sum(|r| r.parse)),
+        format_duration(sum(|r| r.part_one.duration)),
+        format_duration(sum(|r| r.part_two.duration)),
+        format_duration(sum(DayReport::total)),
+    ];
+    format_table(&header, &rows, Some(&total))
+}
+
+/// Lays out `rows` under `header` with aligned columns, the first one left aligned and the
+/// rest right aligned. A `footer` is set apart by a separator line.
+pub(crate) fn format_table<const N: usize>(
+    header: &[String; N],
+    rows: &[[String; N]],
+    footer: Option<&[String; N]>,
+) -> String {
+    let mut widths = [0usize; N];
+    for row in rows.iter().chain([header]).chain(footer) {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = usize::max(*width, cell.len());
+        }
+    }
+
+    let format_row = |row: &[String; N]| {
+        row.iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                if i == 0 {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+    let separator = widths
+        .iter()
+        .map(|w| "-".repeat(*w))
+        .collect::<Vec<_>>()
+        .join("-+-");
+
+    let mut lines = vec![format_row(header), separator.clone()];
+    lines.extend(rows.iter().map(format_row));
+    if let Some(footer) = footer {
+        lines.push(separator);
+        lines.push(format_row(footer));
+    }
+    lines.join("\n")
+}
+
+/// One part as a JSON object. The status is `ok`, or the verdict when answers are checked.
+pub fn part_json(day: u32, part: u8, report: &PartReport, verdict: Option<&Verdict>) -> String {
+    let object = Object::new()
+        .number("day", day)
+        .number("part", part)
+        .string("answer", &report.answer)
+        .number("duration_ms", format!("{:.3}", millis(report.duration)));
+    match verdict {
+        None => object.string("status", "ok"),
+        Some(Verdict::Pass) => object.string("status", "pass"),
+        Some(Verdict::Missing) => object.string("status", "missing"),
+        Some(Verdict::Fail(expected)) => {
+            object.string("status", "fail").string("expected", expected)
+        }
+    }
+    .to_string()
+}
+
+/// A part that has no answer because its day could not be read or solved.
+pub fn error_json(day: u32, part: u8, error: &str) -> String {
+    Object::new()
+        .number("day", day)
+        .number("part", part)
+        .null("answer")
+        .null("duration_ms")
+        .string("status", "error")
+        .string("error", error)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn selection() {
+        assert_eq!(Ok(5..=5), parse_selection("5"));
+        assert_eq!(Ok(1..=10), parse_selection("1..=10"));
+        assert_eq!(Ok(1..=9), parse_selection("1..10"));
+        assert_eq!(Ok(1..=25), parse_selection("all"));
+        assert!(parse_selection("one").is_err());
+        assert_eq!(
+            vec![3, 4],
+            select(&(3..=4))
+                .iter()
+                .map(|d| d.number)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn table() {
+        let part = |ms| PartReport {
+            answer: String::new(),
+            duration: Duration::from_millis(ms),
+        };
+        let reports = vec![
+            DayReport {
+                day: 1,
+                parse: Duration::from_millis(1),
+                part_one: part(2),
+                part_two: part(3),
+            },
+            DayReport {
+                day: 12,
+                parse: Duration::from_millis(10),
+                part_one: part(200),
+                part_two: part(3000),
+            },
+        ];
+        let expected = "\
+Day   |     Parse |     Part 1 |      Part 2 |       Total
+------+-----------+------------+-------------+------------
+1     |  1.000 ms |   2.000 ms |    3.000 ms |    6.000 ms
+12    | 10.000 ms | 200.000 ms | 3000.000 ms | 3210.000 ms
+------+-----------+------------+-------------+------------
+Total | 11.000 ms | 202.000 ms | 3003.000 ms | 3216.000 ms";
+        assert_eq!(expected, timing_table(&reports));
+    }
+
+    #[test]
+    fn json() {
+        let report = PartReport {
+            answer: "42".to_string(),
+            duration: Duration::from_micros(1500),
+        };
+        assert_eq!(
+            r#"{"day":7,"part":1,"answer":"42","duration_ms":1.500,"status":"ok"}"#,
+            part_json(7, 1, &report, None)
+        );
+        assert_eq!(
+            r#"{"day":7,"part":2,"answer":"42","duration_ms":1.500,"status":"fail","expected":"41"}"#,
+            part_json(7, 2, &report, Some(&Verdict::Fail("41".to_string())))
+        );
+        assert_eq!(
+            r#"{"day":7,"part":1,"answer":null,"duration_ms":null,"status":"error","error":"oops"}"#,
+            error_json(7, 1, "oops")
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! The [`Solution`] trait every day implements, and the registry of all days.
+
+use std::fmt::Display;
+
+use crate::error::Result;
+
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day3, day4, day5, day6, day7, day8, day9,
+};
+
+/// A single day's puzzle: the input is parsed once and both parts are answered from it.
+pub trait Solution {
+    type Input;
+    type PartOne: Display;
+    type PartTwo: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne>;
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo>;
+}
+
+/// Type erased [`Solution`], so days with different input types can live in one registry.
+pub trait Runner {
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>>;
+}
+
+/// Parsed input of a [`Runner`], ready to answer both parts.
+pub trait Parsed {
+    fn part_one(&self) -> Result<String>;
+    fn part_two(&self) -> Result<String>;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution + 'static> Runner for S {
+    fn parse(&self, input: &str) -> Result<Box<dyn Parsed>> {
+        Ok(Box::new(ParsedInput::<S>(S::parse(input)?)))
+    }
+}
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn part_one(&self) -> Result<String> {
+        S::part_one(&self.0).map(|answer| answer.to_string())
+    }
+
+    fn part_two(&self) -> Result<String> {
+        S::part_two(&self.0).map(|answer| answer.to_string())
+    }
+}
+
+pub struct Day {
+    pub number: u32,
+    pub runner: Box<dyn Runner>,
+}
+
+impl Day {
+    fn new<S: Solution + 'static>(number: u32, solution: S) -> Self {
+        Self {
+            number,
+            runner: Box::new(solution),
+        }
+    }
+}
+
+/// Every implemented day, ordered by day number.
+pub fn registry() -> Vec<Day> {
+    vec![
+        Day::new(1, day1::Day1),
+        Day::new(2, day2::Day2),
+        Day::new(3, day3::Day3),
+        Day::new(4, day4::Day4),
+        Day::new(5, day5::Day5),
+        Day::new(6, day6::Day6),
+        Day::new(7, day7::Day7),
+        Day::new(8, day8::Day8),
+        Day::new(9, day9::Day9),
+        Day::new(10, day10::Day10),
+        Day::new(11, day11::Day11),
+        Day::new(12, day12::Day12),
+        Day::new(13, day13::Day13),
+        Day::new(14, day14::Day14),
+        Day::new(15, day15::Day15),
+        Day::new(16, day16::Day16),
+        Day::new(17, day17::Day17),
+        Day::new(18, day18::Day18),
+        Day::new(19, day19::Day19),
+        Day::new(20, day20::Day20),
+        Day::new(21, day21::Day21),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registry_has_every_day() {
+        let numbers: Vec<u32> = registry().iter().map(|d| d.number).collect();
+        assert_eq!((1..=21).collect::<Vec<_>>(), numbers);
+    }
+}
@@ -0,0 +1,45 @@
+use aoc_2021::day16::Packet;
+use aoc_2021::day18::Number;
+use aoc_2021::neighbors::{neighbors, Bounds, Neighborhood};
+use aoc_2021::reader::parse_grid;
+use aoc_2021::runner;
+
+#[test]
+fn packet() {
+    let packet = Packet::from_hex("9C0141080250320F1802104A08").unwrap();
+    assert_eq!(Ok(1), packet.value());
+    assert_eq!(20, packet.version_sum());
+}
+
+#[test]
+fn snailfish() {
+    let number = Number::parse("[[1,2],[[3,4],5]]").unwrap();
+    assert_eq!("[[1,2],[[3,4],5]]", number.to_string());
+    assert_eq!(143, number.magnitude());
+
+    let sum = Number::sum(["[[[[4,3],4],4],[7,[[8,4],9]]]", "[1,1]"]).unwrap();
+    assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", sum.to_string());
+    //Debug leaves out the links back to the parents
+    assert!(format!("{:?}", sum).starts_with("Number { id: "));
+}
+
+#[test]
+fn grid_neighbors() {
+    let ((row_size, col_size), cells) = parse_grid("123\n456").unwrap();
+    assert_eq!((3, 2), (row_size, col_size));
+    assert_eq!(vec![1, 2, 3, 4, 5, 6], cells);
+
+    let bounds = Bounds::new(row_size as u32, col_size as u32);
+    let around: Vec<_> = neighbors((0, 0), bounds, Neighborhood::Eight).collect();
+    assert_eq!(vec![(1, 0), (0, 1), (1, 1)], around);
+    let around: Vec<_> = neighbors((2, 1), bounds, Neighborhood::Four).collect();
+    assert_eq!(vec![(2, 0), (1, 1)], around);
+}
+
+#[test]
+fn run_day() {
+    let days = runner::select(&runner::parse_selection("6").unwrap());
+    let report = runner::run(&days[0], "3,4,3,1,2").unwrap();
+    assert_eq!("5934", report.part_one.answer);
+    assert_eq!("26984457539", report.part_two.answer);
+}
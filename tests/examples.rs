@@ -0,0 +1,57 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use aoc_2021::answers::Verdict;
+use aoc_2021::examples::{examples, Example, EXAMPLES_DIR};
+
+#[test]
+fn all_examples() {
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    for day in 1..=25 {
+        for example in examples(Path::new(EXAMPLES_DIR), day).unwrap() {
+            match example.check() {
+                Ok(verdicts) => {
+                    for (part, verdict) in verdicts {
+                        checked += 1;
+                        if verdict != Verdict::Pass {
+                            failures.push(format!(
+                                "day {}-{} on {}: {}",
+                                day, part, example.name, verdict
+                            ));
+                        }
+                    }
+                }
+                Err(e) => failures.push(format!("day {} on {}: {}", day, example.name, e)),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    assert!(checked > 0);
+}
+
+#[test]
+fn answers_for_another_day() {
+    let dir = env::temp_dir().join(format!("aoc_2021_examples_{}", std::process::id()));
+    fs::create_dir_all(dir.join("day15")).unwrap();
+    fs::write(dir.join("day15/example.txt"), "1163751742\n").unwrap();
+    let load = |toml: &str| {
+        fs::write(dir.join("day15/example.toml"), toml).unwrap();
+        Example::load(&dir, 15, "example").map(|example| example.answers)
+    };
+    assert!(load("[day15]\npart1 = 40\n").is_ok());
+    for toml in [
+        "[day16]\npart1 = 40\n",
+        "[day15]\npart1 = 40\n[day16]\npart2 = 1\n",
+        "",
+    ] {
+        let error = load(toml).unwrap_err().to_string();
+        assert!(
+            error.ends_with("example.toml: expected answers under [day15]"),
+            "{}",
+            error
+        );
+    }
+    fs::remove_dir_all(dir).unwrap();
+}